@@ -1,3 +1,6 @@
+// the demonstration functions are kept as written
+#![allow(clippy::missing_safety_doc, clippy::map_clone)]
+
 extern crate fast_floats;
 
 use fast_floats::Fast;
@@ -0,0 +1,777 @@
+//! Streaming accumulators.
+
+use crate::{Fast, FastFloat};
+
+/// Return `m 2^e`, rounding only once at the end.
+fn ldexp(m: f64, e: i64) -> f64 {
+    // 2^k for k in the normal range
+    fn pow2(k: i64) -> f64 { f64::from_bits(((k + 1023) as u64) << 52) }
+    // beyond this the result is zero or infinite for any m in 0.5..1
+    let e = e.clamp(-2200, 2200);
+    let (a, b) = (e / 3, (e - e / 3) / 2);
+    m * pow2(a) * pow2(b) * pow2(e - a - b)
+}
+
+/// A running product that can not overflow or underflow.
+///
+/// The product is stored as a mantissa and a separate binary exponent. Each factor is
+/// split into mantissa and exponent before it is multiplied in, and the running mantissa
+/// is renormalized every `interval` factors, so that it always stays in the normal range.
+///
+/// ```
+/// use fast_floats::accum::RunningProduct;
+/// use fast_floats::Fast;
+///
+/// let mut p = RunningProduct::<f64>::new();
+/// for _ in 0..100 {
+///     p.push(unsafe { Fast::new(1e-10) });
+/// }
+/// // 1e-1000 is not representable, but its exponent is
+/// let (_, exp) = p.parts();
+/// assert_eq!(exp, -3321);
+/// assert_eq!(p.value(), 0.);
+/// ```
+#[derive(Copy, Clone, Debug)]
+pub struct RunningProduct<F> {
+    mantissa: Fast<F>,
+    exponent: i64,
+    interval: u32,
+    pending: u32,
+}
+
+impl<F: FastFloat> RunningProduct<F> {
+    /// The largest renormalization interval; a product of this many mantissas stays in the
+    /// normal range for both `f32` and `f64`.
+    pub const MAX_INTERVAL: u32 = 120;
+
+    /// Create a new running product with the value one, renormalizing every 32 factors.
+    pub fn new() -> Self {
+        Self::with_interval(32)
+    }
+
+    /// Create a new running product with the value one, renormalizing every `interval`
+    /// factors.
+    ///
+    /// ***Panics*** if `interval` is not in `1..=MAX_INTERVAL`.
+    pub fn with_interval(interval: u32) -> Self {
+        assert!((1..=Self::MAX_INTERVAL).contains(&interval),
+                "RunningProduct: interval must be in 1..={}", Self::MAX_INTERVAL);
+        RunningProduct { mantissa: Fast(F::ONE), exponent: 0, interval, pending: 0 }
+    }
+
+    /// Multiply `x` into the product
+    #[inline]
+    pub fn push(&mut self, x: Fast<F>) {
+        let (m, e) = x.0.frexp();
+        self.mantissa *= m;
+        self.exponent += e as i64;
+        self.pending += 1;
+        if self.pending == self.interval {
+            self.renormalize();
+        }
+    }
+
+    fn renormalize(&mut self) {
+        let (m, e) = self.mantissa.0.frexp();
+        self.mantissa = Fast(m);
+        self.exponent += e as i64;
+        self.pending = 0;
+    }
+
+    /// Return `(m, e)` such that the product is `m 2^e` and `0.5 <= |m| < 1`, or `m` is zero.
+    pub fn parts(&self) -> (Fast<F>, i64) {
+        let mut p = *self;
+        p.renormalize();
+        (p.mantissa, p.exponent)
+    }
+
+    /// Return the value of the product.
+    ///
+    /// The result is a regular float, since it is infinite if the product overflows.
+    pub fn value(&self) -> F {
+        F::from_f64(ldexp(self.mantissa.0.to_f64(), self.exponent))
+    }
+}
+
+impl<F: FastFloat> Default for RunningProduct<F> {
+    fn default() -> Self { Self::new() }
+}
+
+/// A running sum.
+///
+/// Sums can be merged, for example to combine sums of chunks computed in parallel.
+///
+/// ```
+/// use fast_floats::accum::FastSum;
+/// use fast_floats::Fast;
+///
+/// let xs = [1., 2., 3., 4.].map(|x| unsafe { Fast::new(x) });
+/// let mut a: FastSum<f64> = xs[..2].iter().collect();
+/// let b: FastSum<f64> = xs[2..].iter().collect();
+/// a.merge(b);
+/// assert_eq!(a.finish().get(), 10.);
+/// ```
+#[derive(Copy, Clone, Debug)]
+pub struct FastSum<F> {
+    sum: Fast<F>,
+}
+
+impl<F: FastFloat> FastSum<F> {
+    /// Create a new sum with the value zero
+    pub fn new() -> Self { FastSum { sum: Fast(F::ZERO) } }
+
+    /// Add `x` to the sum
+    #[inline]
+    pub fn push(&mut self, x: Fast<F>) { self.sum += x; }
+
+    /// Add the sum `other` to this sum
+    #[inline]
+    pub fn merge(&mut self, other: Self) { self.sum += other.sum; }
+
+    /// Return the sum
+    pub fn finish(&self) -> Fast<F> { self.sum }
+}
+
+/// A running arithmetic mean.
+///
+/// Means can be merged, for example to combine means of chunks computed in parallel.
+#[derive(Copy, Clone, Debug)]
+pub struct FastMean<F> {
+    sum: Fast<F>,
+    count: u64,
+}
+
+impl<F: FastFloat> FastMean<F> {
+    /// Create a new mean of no values
+    pub fn new() -> Self { FastMean { sum: Fast(F::ZERO), count: 0 } }
+
+    /// Add `x` to the mean
+    #[inline]
+    pub fn push(&mut self, x: Fast<F>) {
+        self.sum += x;
+        self.count += 1;
+    }
+
+    /// Add the values of the mean `other` to this mean
+    #[inline]
+    pub fn merge(&mut self, other: Self) {
+        self.sum += other.sum;
+        self.count += other.count;
+    }
+
+    /// Return the number of values
+    pub fn count(&self) -> u64 { self.count }
+
+    /// Return the mean, or zero if there are no values
+    pub fn finish(&self) -> Fast<F> {
+        if self.count == 0 {
+            return Fast(F::ZERO);
+        }
+        self.sum / F::from_f64(self.count as f64)
+    }
+}
+
+/// A running product.
+///
+/// Products can be merged, for example to combine products of chunks computed in
+/// parallel. The product is not guarded against overflow; see [`RunningProduct`] for that.
+#[derive(Copy, Clone, Debug)]
+pub struct FastProduct<F> {
+    product: Fast<F>,
+}
+
+impl<F: FastFloat> FastProduct<F> {
+    /// Create a new product with the value one
+    pub fn new() -> Self { FastProduct { product: Fast(F::ONE) } }
+
+    /// Multiply `x` into the product
+    #[inline]
+    pub fn push(&mut self, x: Fast<F>) { self.product *= x; }
+
+    /// Multiply the product `other` into this product
+    #[inline]
+    pub fn merge(&mut self, other: Self) { self.product *= other.product; }
+
+    /// Return the product
+    pub fn finish(&self) -> Fast<F> { self.product }
+}
+
+macro_rules! impl_collect {
+    ($($name:ident)*) => {
+        $(
+        impl<F: FastFloat> Default for $name<F> {
+            fn default() -> Self { Self::new() }
+        }
+
+        impl<F: FastFloat> Extend<Fast<F>> for $name<F> {
+            fn extend<I: IntoIterator<Item = Fast<F>>>(&mut self, iter: I) {
+                for x in iter {
+                    self.push(x);
+                }
+            }
+        }
+
+        impl<'a, F: FastFloat> Extend<&'a Fast<F>> for $name<F> {
+            fn extend<I: IntoIterator<Item = &'a Fast<F>>>(&mut self, iter: I) {
+                self.extend(iter.into_iter().copied())
+            }
+        }
+
+        impl<F: FastFloat> FromIterator<Fast<F>> for $name<F> {
+            fn from_iter<I: IntoIterator<Item = Fast<F>>>(iter: I) -> Self {
+                let mut acc = Self::new();
+                acc.extend(iter);
+                acc
+            }
+        }
+
+        impl<'a, F: FastFloat> FromIterator<&'a Fast<F>> for $name<F> {
+            fn from_iter<I: IntoIterator<Item = &'a Fast<F>>>(iter: I) -> Self {
+                iter.into_iter().copied().collect()
+            }
+        }
+        )*
+    }
+}
+
+impl_collect!(FastSum FastMean FastProduct TreeSum);
+
+/// An extension trait for iterators of `Fast` values.
+pub trait TreeSumExt<F>: Iterator<Item = Fast<F>> + Sized {
+    /// Return the sum of the values, associated in a fixed binary tree, see [`TreeSum`]
+    fn fast_tree_sum(self) -> Fast<F>;
+}
+
+impl<F: FastFloat, I: Iterator<Item = Fast<F>>> TreeSumExt<F> for I {
+    fn fast_tree_sum(self) -> Fast<F> {
+        self.collect::<TreeSum<F>>().finish()
+    }
+}
+
+/// An exponential moving average.
+///
+/// Each new value `x` updates the average as `avg = avg + alpha (x - avg)`.
+///
+/// Without bias correction the average starts at the first value. With bias correction it
+/// starts at zero and is divided by `1 - (1 - alpha)^n` after `n` values, as in the Adam
+/// optimizer, so that early values are not underweighted.
+///
+/// ```
+/// use fast_floats::accum::Ema;
+/// use fast_floats::Fast;
+///
+/// let f = |x| unsafe { Fast::new(x) };
+/// let mut ema = Ema::new(f(0.5));
+/// ema.extend([f(4.), f(2.), f(1.)]);
+/// assert_eq!(ema.value(), f(2.));
+/// ```
+#[derive(Copy, Clone, Debug)]
+pub struct Ema<F> {
+    alpha: Fast<F>,
+    avg: Fast<F>,
+    // (1 - alpha)^n with bias correction, and otherwise one until the first value
+    decay: Fast<F>,
+    bias_correction: bool,
+}
+
+impl<F: FastFloat> Ema<F> {
+    /// Create a new average with smoothing factor `alpha`.
+    ///
+    /// ***Panics*** if `alpha` is not in `0 < alpha <= 1`.
+    pub fn new(alpha: Fast<F>) -> Self {
+        Self::with_options(alpha, false)
+    }
+
+    /// Create a new average with smoothing factor `alpha`, and bias correction.
+    ///
+    /// ***Panics*** if `alpha` is not in `0 < alpha <= 1`.
+    pub fn with_bias_correction(alpha: Fast<F>) -> Self {
+        Self::with_options(alpha, true)
+    }
+
+    fn with_options(alpha: Fast<F>, bias_correction: bool) -> Self {
+        assert!(alpha > Fast(F::ZERO) && alpha <= Fast(F::ONE),
+                "Ema: alpha must be in the range 0 < alpha <= 1, got {:?}", alpha);
+        Ema { alpha, avg: Fast(F::ZERO), decay: Fast(F::ONE), bias_correction }
+    }
+
+    /// Update the average with `x`
+    #[inline]
+    pub fn push(&mut self, x: Fast<F>) {
+        if self.bias_correction {
+            self.decay *= -self.alpha + F::ONE;
+        } else if self.decay != Fast(F::ZERO) {
+            self.avg = x;
+            self.decay = Fast(F::ZERO);
+            return;
+        }
+        self.avg = self.alpha * (x - self.avg) + self.avg;
+    }
+
+    /// Return the average, or zero if there are no values
+    pub fn value(&self) -> Fast<F> {
+        if self.bias_correction && self.decay != Fast(F::ONE) {
+            self.avg / (-self.decay + F::ONE)
+        } else {
+            self.avg
+        }
+    }
+}
+
+impl<F: FastFloat> Extend<Fast<F>> for Ema<F> {
+    fn extend<I: IntoIterator<Item = Fast<F>>>(&mut self, iter: I) {
+        for x in iter {
+            self.push(x);
+        }
+    }
+}
+
+/// A streaming quantile estimator, using the P² algorithm by Jain and Chlamtac.
+///
+/// The estimator uses constant memory: it keeps five markers, whose heights are adjusted
+/// with piecewise parabolic interpolation as values are pushed. After the first five values
+/// the estimate is approximate; it is typically accurate for smooth distributions.
+///
+/// ```
+/// use fast_floats::accum::P2Quantile;
+/// use fast_floats::Fast;
+///
+/// let mut median = P2Quantile::new(unsafe { Fast::new(0.5) });
+/// for i in 0..1001 {
+///     median.push(unsafe { Fast::new((i * 7919 % 1001) as f64) });
+/// }
+/// assert!((median.quantile().get() - 500.).abs() < 10.);
+/// ```
+#[derive(Copy, Clone, Debug)]
+pub struct P2Quantile<F> {
+    p: Fast<F>,
+    count: u64,
+    // marker heights; the first values pushed until there are five
+    heights: [Fast<F>; 5],
+    positions: [i64; 5],
+    desired: [Fast<F>; 5],
+}
+
+impl<F: FastFloat> P2Quantile<F> {
+    /// Create an estimator for the `p`-quantile, for example `0.5` for the median.
+    ///
+    /// ***Panics*** if `p` is not in `0..=1`.
+    pub fn new(p: Fast<F>) -> Self {
+        assert!(p >= Fast(F::ZERO) && p <= Fast(F::ONE),
+                "P2Quantile: p must be in the range 0..=1, got {:?}", p);
+        let zero = Fast(F::ZERO);
+        P2Quantile { p, count: 0, heights: [zero; 5], positions: [0; 5], desired: [zero; 5] }
+    }
+
+    /// Return the number of values
+    pub fn count(&self) -> u64 { self.count }
+
+    /// Add `x` to the estimator
+    pub fn push(&mut self, x: Fast<F>) {
+        if self.count < 5 {
+            self.heights[self.count as usize] = x;
+            self.count += 1;
+            if self.count == 5 {
+                self.heights.sort_unstable_by(Fast::total_cmp);
+                let (one, p) = (Fast(F::ONE), self.p);
+                let two = one + one;
+                self.positions = [1, 2, 3, 4, 5];
+                self.desired = [one, two * p + one, two * two * p + one, two * p + one + two,
+                                two * two + one];
+            }
+            return;
+        }
+        self.count += 1;
+        let q = &mut self.heights;
+        let k = if x < q[0] {
+            q[0] = x;
+            0
+        } else if x >= q[4] {
+            q[4] = x;
+            3
+        } else {
+            (1..4).find(|&i| x < q[i]).unwrap_or(4) - 1
+        };
+        for n in &mut self.positions[k + 1..] {
+            *n += 1;
+        }
+        let (p, half) = (self.p, Fast(F::from_f64(0.5)));
+        let increments = [Fast(F::ZERO), p * half, p, p * half + half, Fast(F::ONE)];
+        for (d, &inc) in self.desired.iter_mut().zip(&increments) {
+            *d += inc;
+        }
+        for i in 1..4 {
+            self.adjust(i);
+        }
+    }
+
+    /// Move marker `i` if it is off its desired position by one or more
+    fn adjust(&mut self, i: usize) {
+        let (q, n) = (&mut self.heights, &mut self.positions);
+        let d = self.desired[i] - F::from_f64(n[i] as f64);
+        let one = Fast(F::ONE);
+        let step = if d >= one && n[i + 1] - n[i] > 1 {
+            1
+        } else if d <= -one && n[i - 1] - n[i] < -1 {
+            -1
+        } else {
+            return;
+        };
+        let nf = |j: usize| Fast(F::from_f64(n[j] as f64));
+        let ds = Fast(F::from_f64(step as f64));
+        // piecewise parabolic prediction
+        let parabolic = q[i] + ds / (nf(i + 1) - nf(i - 1))
+            * ((nf(i) - nf(i - 1) + ds) * (q[i + 1] - q[i]) / (nf(i + 1) - nf(i))
+               + (nf(i + 1) - nf(i) - ds) * (q[i] - q[i - 1]) / (nf(i) - nf(i - 1)));
+        q[i] = if q[i - 1] < parabolic && parabolic < q[i + 1] {
+            parabolic
+        } else {
+            let j = (i as i64 + step) as usize;
+            q[i] + ds * (q[j] - q[i]) / (nf(j) - nf(i))
+        };
+        n[i] += step;
+    }
+
+    /// Return the estimate of the quantile, or zero if there are no values.
+    ///
+    /// With fewer than five values, the result is the nearest rank quantile of the values.
+    pub fn quantile(&self) -> Fast<F> {
+        match self.count {
+            0 => Fast(F::ZERO),
+            1..=4 => {
+                let mut xs = self.heights;
+                let xs = &mut xs[..self.count as usize];
+                xs.sort_unstable_by(Fast::total_cmp);
+                let rank = (self.p * F::from_f64((xs.len() - 1) as f64)).0.round_ties_even();
+                xs[rank.to_usize()]
+            }
+            _ => self.heights[2],
+        }
+    }
+}
+
+impl<F: FastFloat> Extend<Fast<F>> for P2Quantile<F> {
+    fn extend<I: IntoIterator<Item = Fast<F>>>(&mut self, iter: I) {
+        for x in iter {
+            self.push(x);
+        }
+    }
+}
+
+/// A running sum that associates the additions in a fixed binary tree.
+///
+/// The values are buffered in blocks of 32, each block is summed pairwise, and the block
+/// sums are combined pairwise like a binary counter. The order of additions depends only
+/// on the number of values, so the result is reproducible across targets and compiler
+/// versions. The additions are regular IEEE additions, because fast-math additions would
+/// let the compiler reassociate them; the pairwise sum of a block still vectorizes.
+///
+/// Pairwise summation also has a smaller error bound than a sequential sum, growing with
+/// `log n` instead of `n`.
+///
+/// ```
+/// use fast_floats::accum::{TreeSum, TreeSumExt};
+/// use fast_floats::Fast;
+///
+/// let xs = [0.1f32; 1000].map(|x| unsafe { Fast::new(x) });
+/// let sum = xs.iter().copied().fast_tree_sum();
+/// assert_eq!(sum, xs.iter().collect::<TreeSum<f32>>().finish());
+/// assert!((sum.get() - 100.).abs() < 1e-4);
+/// ```
+#[derive(Copy, Clone, Debug)]
+pub struct TreeSum<F> {
+    block: [Fast<F>; TREE_BLOCK],
+    len: usize,
+    // levels[k] holds the sum of 2^k blocks if bit k of `blocks` is set
+    levels: [Fast<F>; 64],
+    blocks: u64,
+}
+
+const TREE_BLOCK: usize = 32;
+
+impl<F: FastFloat> TreeSum<F> {
+    /// Create a new sum with the value zero
+    pub fn new() -> Self {
+        let zero = Fast(F::ZERO);
+        TreeSum { block: [zero; TREE_BLOCK], len: 0, levels: [zero; 64], blocks: 0 }
+    }
+
+    /// Add `x` to the sum
+    #[inline]
+    pub fn push(&mut self, x: Fast<F>) {
+        self.block[self.len] = x;
+        self.len += 1;
+        if self.len == TREE_BLOCK {
+            let s = Self::block_sum(self.block);
+            self.push_block(s);
+            self.len = 0;
+        }
+    }
+
+    /// Add elements of `xs` in full blocks, for better vectorization
+    pub fn extend_from_slice(&mut self, mut xs: &[Fast<F>]) {
+        while !xs.is_empty() {
+            if self.len == 0 && xs.len() >= TREE_BLOCK {
+                let (block, rest) = xs.split_at(TREE_BLOCK);
+                let s = Self::block_sum(block.try_into().unwrap());
+                self.push_block(s);
+                xs = rest;
+            } else {
+                self.push(xs[0]);
+                xs = &xs[1..];
+            }
+        }
+    }
+
+    #[inline]
+    fn block_sum(mut block: [Fast<F>; TREE_BLOCK]) -> Fast<F> {
+        let mut n = TREE_BLOCK;
+        while n > 1 {
+            n /= 2;
+            let (lo, hi) = block.split_at_mut(n);
+            for (a, b) in lo.iter_mut().zip(&*hi) {
+                *a = Fast(F::add_strict(a.0, b.0));
+            }
+        }
+        block[0]
+    }
+
+    fn push_block(&mut self, mut s: Fast<F>) {
+        let mut level = 0;
+        while self.blocks & (1 << level) != 0 {
+            s = Fast(F::add_strict(self.levels[level].0, s.0));
+            level += 1;
+        }
+        self.levels[level] = s;
+        // the carries clear the bits below `level` and set bit `level`
+        self.blocks += 1;
+    }
+
+    /// Return the sum
+    pub fn finish(&self) -> Fast<F> {
+        // the partial block is padded with zeros, which keeps the tree shape fixed
+        let mut block = [Fast(F::ZERO); TREE_BLOCK];
+        block[..self.len].copy_from_slice(&self.block[..self.len]);
+        let mut sum = Self::block_sum(block);
+        for level in 0..64 {
+            if self.blocks & (1 << level) != 0 {
+                sum = Fast(F::add_strict(self.levels[level].0, sum.0));
+            }
+        }
+        sum
+    }
+}
+
+/// Statistics of the last `N` values: mean, variance, minimum and maximum.
+///
+/// The values are kept in a ring buffer of fixed size, so no allocation is needed. The
+/// mean and variance are updated in constant time per value, with Welford's update for a
+/// sliding window; they are recomputed from the buffer each time it wraps around, so that
+/// the rounding errors of the updates do not accumulate. The minimum and maximum are
+/// computed from the buffer when they are requested.
+///
+/// ```
+/// use fast_floats::accum::WindowStats;
+/// use fast_floats::Fast;
+///
+/// let f = |x| unsafe { Fast::new(x) };
+/// let mut window = WindowStats::<f64, 3>::new();
+/// window.extend([f(10.), f(1.), f(2.), f(3.)]);
+/// assert_eq!(window.mean(), f(2.));
+/// assert_eq!((window.min(), window.max()), (Some(f(1.)), Some(f(3.))));
+/// ```
+#[derive(Copy, Clone, Debug)]
+pub struct WindowStats<F, const N: usize> {
+    values: [Fast<F>; N],
+    // the index of the oldest value when the window is full
+    head: usize,
+    len: usize,
+    mean: Fast<F>,
+    // the sum of squared differences from the mean
+    m2: Fast<F>,
+}
+
+impl<F: FastFloat, const N: usize> WindowStats<F, N> {
+    /// Create a new, empty window
+    pub fn new() -> Self {
+        const { assert!(N > 0, "WindowStats: N must not be zero") };
+        let zero = Fast(F::ZERO);
+        WindowStats { values: [zero; N], head: 0, len: 0, mean: zero, m2: zero }
+    }
+
+    /// Add `x` to the window, replacing the oldest value if the window is full
+    #[inline]
+    pub fn push(&mut self, x: Fast<F>) {
+        if self.len < N {
+            self.values[self.len] = x;
+            self.len += 1;
+            let delta = x - self.mean;
+            self.mean += delta / Fast(F::from_f64(self.len as f64));
+            self.m2 += delta * (x - self.mean);
+            return;
+        }
+        let old = core::mem::replace(&mut self.values[self.head], x);
+        self.head += 1;
+        if self.head == N {
+            self.head = 0;
+            self.recompute();
+        } else {
+            let delta = x - old;
+            let mean = delta * F::from_f64(1. / N as f64) + self.mean;
+            self.m2 += delta * (x - mean + old - self.mean);
+            self.mean = mean;
+        }
+    }
+
+    fn recompute(&mut self) {
+        let values = &self.values[..self.len];
+        self.mean = crate::slice::sum(values) / F::from_f64(self.len as f64);
+        let mean = self.mean;
+        self.m2 = values.iter().fold(Fast(F::ZERO), |acc, &x| (x - mean) * (x - mean) + acc);
+    }
+
+    /// Return the number of values in the window
+    pub fn len(&self) -> usize { self.len }
+
+    /// Return `true` if the window has no values
+    pub fn is_empty(&self) -> bool { self.len == 0 }
+
+    /// Return `true` if the window has `N` values
+    pub fn is_full(&self) -> bool { self.len == N }
+
+    /// Return the mean of the values, or zero if there are none
+    pub fn mean(&self) -> Fast<F> { self.mean }
+
+    /// Return the (population) variance of the values, or zero if there are none
+    pub fn variance(&self) -> Fast<F> {
+        if self.len == 0 {
+            return Fast(F::ZERO);
+        }
+        (self.m2 / F::from_f64(self.len as f64)).max(Fast(F::ZERO))
+    }
+
+    /// Return the smallest value, or `None` if there are none
+    pub fn min(&self) -> Option<Fast<F>> {
+        self.values[..self.len].iter().copied().reduce(Fast::min)
+    }
+
+    /// Return the largest value, or `None` if there are none
+    pub fn max(&self) -> Option<Fast<F>> {
+        self.values[..self.len].iter().copied().reduce(Fast::max)
+    }
+
+    /// Remove all values
+    pub fn reset(&mut self) { *self = Self::new(); }
+}
+
+impl<F: FastFloat, const N: usize> Default for WindowStats<F, N> {
+    fn default() -> Self { Self::new() }
+}
+
+impl<F: FastFloat, const N: usize> Extend<Fast<F>> for WindowStats<F, N> {
+    fn extend<I: IntoIterator<Item = Fast<F>>>(&mut self, iter: I) {
+        for x in iter {
+            self.push(x);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::float::sealed::Sealed;
+
+    #[test]
+    fn running_product() {
+        let mut p = RunningProduct::<f32>::with_interval(1);
+        for _ in 0..200 {
+            p.push(Fast(1e30));
+        }
+        for _ in 0..200 {
+            p.push(Fast(1e-30));
+        }
+        p.push(Fast(-3.));
+        assert!((p.value() + 3.).abs() < 1e-3);
+        assert_eq!(f32::MIN_POSITIVE.frexp(), (0.5, -125));
+        assert_eq!((f32::MIN_POSITIVE / 4.).frexp(), (0.5, -127));
+        assert_eq!(ldexp(0.5, -1074), 0.);
+        assert_eq!(ldexp(0.5, -1073), 5e-324);
+        assert_eq!(ldexp(0.5, 1025), f64::INFINITY);
+    }
+
+    #[test]
+    fn accumulators() {
+        let xs = [Fast(1.), Fast(2.), Fast(3.), Fast(6.)];
+        let mut mean: FastMean<f64> = xs[..1].iter().collect();
+        mean.extend(&xs[1..]);
+        assert_eq!((mean.count(), mean.finish()), (4, Fast(3.)));
+        assert_eq!(FastMean::<f32>::new().finish(), Fast(0.));
+        let mut sum = FastSum::new();
+        sum.extend(xs);
+        sum.merge(xs.iter().collect());
+        assert_eq!(sum.finish(), Fast(24.));
+        let mut product: FastProduct<f64> = xs.into_iter().collect();
+        product.merge(FastProduct::default());
+        assert_eq!(product.finish(), Fast(36.));
+    }
+
+    #[test]
+    fn window() {
+        let mut window = WindowStats::<f64, 4>::new();
+        assert_eq!((window.mean(), window.variance(), window.min()), (Fast(0.), Fast(0.), None));
+        for i in 0..11 {
+            window.push(Fast(i as f64 * 1.5 + 1e6));
+            // the window holds the arithmetic sequence from i + 1 - n to i
+            let n = window.len() as f64;
+            let (mean, var) = (((2 * i + 1) as f64 - n) / 2., (n * n - 1.) / 12.);
+            assert!((window.mean().get() - (mean * 1.5 + 1e6)).abs() < 1e-9, "{}", i);
+            assert!((window.variance().get() - var * 2.25).abs() < 1e-6, "{}", i);
+            assert_eq!(window.max(), Some(Fast(i as f64 * 1.5 + 1e6)));
+        }
+        assert!(window.is_full());
+        window.reset();
+        assert!(window.is_empty());
+    }
+
+    #[test]
+    fn tree_sum() {
+        let xs: [Fast<f64>; 200] = std::array::from_fn(|i| Fast(1. / (i + 1) as f64));
+        for n in [0, 1, 31, 32, 33, 64, 100, 200] {
+            let a = xs[..n].iter().copied().fast_tree_sum();
+            assert!((a - crate::slice::sum(&xs[..n])).abs() < Fast(1e-14), "{}", n);
+            let mut b = TreeSum::new();
+            b.extend_from_slice(&xs[..n]);
+            assert_eq!(a, b.finish());
+        }
+        // the tree shape only depends on the number of values
+        let mut c = TreeSum::new();
+        c.extend(&xs[..7]);
+        c.extend_from_slice(&xs[7..]);
+        assert_eq!(c.finish(), xs.iter().collect::<TreeSum<f64>>().finish());
+    }
+
+    #[test]
+    fn ema() {
+        let mut ema = Ema::with_bias_correction(Fast(0.1));
+        assert_eq!(ema.value(), Fast(0.));
+        ema.push(Fast(5.));
+        assert!((ema.value().get() - 5f64).abs() < 1e-12);
+        ema.push(Fast(5.));
+        assert!((ema.value().get() - 5f64).abs() < 1e-12);
+        let mut ema = Ema::new(Fast(1f32));
+        ema.extend([Fast(1.), Fast(7.)]);
+        assert_eq!(ema.value(), Fast(7.));
+    }
+
+    #[test]
+    fn p2_quantile() {
+        let mut q = P2Quantile::new(Fast(0.9));
+        assert_eq!(q.quantile(), Fast(0.));
+        q.extend([Fast(3.), Fast(1.), Fast(2.)]);
+        assert_eq!(q.quantile(), Fast(3.));
+        // a uniform sequence in a scrambled order
+        q.extend((0..10000).map(|i| Fast((i * 7919 % 10000) as f64)));
+        assert_eq!(q.count(), 10003);
+        assert!((q.quantile().get() - 9000.).abs() < 50., "{:?}", q.quantile());
+    }
+}
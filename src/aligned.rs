@@ -0,0 +1,191 @@
+use std::fmt;
+use std::mem;
+use std::ops::{Deref, DerefMut};
+use std::ptr::{self, NonNull};
+use std::slice;
+
+use alloc::alloc::{alloc, dealloc, handle_alloc_error, Layout};
+
+use crate::Fast;
+
+/// An owned, fixed length buffer with its storage aligned to [`AlignedBuffer::ALIGN`] bytes.
+///
+/// The alignment covers both 32-byte (AVX) and 64-byte (AVX-512, cache line) vector loads,
+/// so kernels that check for alignment can take their aligned fast path on the whole buffer.
+///
+/// The buffer holds plain `F` values; use [`as_fast_slice`](AlignedBuffer::as_fast_slice)
+/// to view them as `Fast<F>`.
+pub struct AlignedBuffer<F> {
+    ptr: NonNull<F>,
+    len: usize,
+    // the length the storage was allocated for; `len` trails it while `from_fn` runs
+    cap: usize,
+}
+
+unsafe impl<F: Send> Send for AlignedBuffer<F> { }
+unsafe impl<F: Sync> Sync for AlignedBuffer<F> { }
+
+impl<F> AlignedBuffer<F> {
+    /// Alignment of the buffer's storage in bytes
+    pub const ALIGN: usize = 64;
+
+    fn layout(len: usize) -> Layout {
+        let align = Ord::max(Self::ALIGN, mem::align_of::<F>());
+        match Layout::array::<F>(len).and_then(|l| l.align_to(align)) {
+            Ok(layout) => layout,
+            Err(_) => panic!("AlignedBuffer: capacity overflow"),
+        }
+    }
+
+    /// Create a buffer of length `len` where each element is produced by `f(index)`.
+    pub fn from_fn(len: usize, mut f: impl FnMut(usize) -> F) -> Self {
+        let layout = Self::layout(len);
+        let ptr = if layout.size() == 0 {
+            NonNull::new(ptr::without_provenance_mut(layout.align())).unwrap()
+        } else {
+            match NonNull::new(unsafe { alloc(layout) } as *mut F) {
+                Some(p) => p,
+                None => handle_alloc_error(layout),
+            }
+        };
+        // Count initialized elements in `buf.len` so that a panic in `f` drops only those;
+        // the storage is freed with the layout for `cap`.
+        let mut buf = AlignedBuffer { ptr, len: 0, cap: len };
+        for i in 0..len {
+            unsafe {
+                buf.ptr.as_ptr().add(i).write(f(i));
+            }
+            buf.len = i + 1;
+        }
+        buf
+    }
+
+    /// Create a buffer of length `len` filled with `value`.
+    pub fn from_elem(len: usize, value: F) -> Self
+        where F: Clone,
+    {
+        Self::from_fn(len, |_| value.clone())
+    }
+
+    /// Create a buffer holding a copy of `values`.
+    pub fn from_slice(values: &[F]) -> Self
+        where F: Clone,
+    {
+        Self::from_fn(values.len(), |i| values[i].clone())
+    }
+
+    /// Return the number of elements in the buffer
+    pub fn len(&self) -> usize { self.len }
+
+    /// Return `true` if the buffer has no elements
+    pub fn is_empty(&self) -> bool { self.len == 0 }
+
+    /// Return a pointer to the first element; it is aligned to `ALIGN` bytes.
+    pub fn as_ptr(&self) -> *const F { self.ptr.as_ptr() }
+
+    /// Return a mutable pointer to the first element; it is aligned to `ALIGN` bytes.
+    pub fn as_mut_ptr(&mut self) -> *mut F { self.ptr.as_ptr() }
+
+    /// View the buffer as a slice
+    pub fn as_slice(&self) -> &[F] {
+        unsafe { slice::from_raw_parts(self.ptr.as_ptr(), self.len) }
+    }
+
+    /// View the buffer as a mutable slice
+    pub fn as_mut_slice(&mut self) -> &mut [F] {
+        unsafe { slice::from_raw_parts_mut(self.ptr.as_ptr(), self.len) }
+    }
+
+    /// View the buffer as a slice of fast values
+    ///
+    /// # Safety
+    ///
+    /// Each element must be a value that is valid to use in `Fast`, see [`Fast::new`].
+    pub unsafe fn as_fast_slice(&self) -> &[Fast<F>] {
+        slice::from_raw_parts(self.ptr.as_ptr() as *const Fast<F>, self.len)
+    }
+
+    /// View the buffer as a mutable slice of fast values
+    ///
+    /// # Safety
+    ///
+    /// Each element must be a value that is valid to use in `Fast`, see [`Fast::new`].
+    pub unsafe fn as_fast_mut_slice(&mut self) -> &mut [Fast<F>] {
+        slice::from_raw_parts_mut(self.ptr.as_ptr() as *mut Fast<F>, self.len)
+    }
+}
+
+impl<F> Drop for AlignedBuffer<F> {
+    fn drop(&mut self) {
+        let layout = Self::layout(self.cap);
+        unsafe {
+            ptr::drop_in_place(self.as_mut_slice());
+            if layout.size() != 0 {
+                dealloc(self.ptr.as_ptr() as *mut u8, layout);
+            }
+        }
+    }
+}
+
+impl<F> Deref for AlignedBuffer<F> {
+    type Target = [F];
+    fn deref(&self) -> &[F] { self.as_slice() }
+}
+
+impl<F> DerefMut for AlignedBuffer<F> {
+    fn deref_mut(&mut self) -> &mut [F] { self.as_mut_slice() }
+}
+
+impl<F: Clone> Clone for AlignedBuffer<F> {
+    fn clone(&self) -> Self { Self::from_slice(self) }
+}
+
+impl<F: fmt::Debug> fmt::Debug for AlignedBuffer<F> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.as_slice().fmt(f)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn aligned() {
+        for len in 0..20 {
+            let buf = AlignedBuffer::from_elem(len, 1.0f32);
+            assert_eq!(buf.as_ptr() as usize % AlignedBuffer::<f32>::ALIGN, 0);
+            assert_eq!(buf.len(), len);
+            assert!(buf.iter().all(|&x| x == 1.));
+        }
+    }
+
+    #[test]
+    fn fast_view() {
+        let mut buf = AlignedBuffer::from_fn(4, |i| i as f64);
+        let fast = unsafe { buf.as_fast_mut_slice() };
+        fast[1] += 1.;
+        assert_eq!(&buf[..], &[0., 2., 2., 3.]);
+    }
+
+    #[cfg(panic = "unwind")]
+    #[test]
+    fn panic_in_from_fn() {
+        extern crate std as libstd;
+        use alloc::rc::Rc;
+        use libstd::panic::{catch_unwind, AssertUnwindSafe};
+
+        // a panic frees the whole allocation and drops exactly the elements made so far
+        let counter = Rc::new(());
+        for fail_at in [0, 1, 5] {
+            let result = catch_unwind(AssertUnwindSafe(|| {
+                AlignedBuffer::from_fn(8, |i| {
+                    assert!(i != fail_at, "from_fn: element {}", i);
+                    counter.clone()
+                })
+            }));
+            assert!(result.is_err());
+            assert_eq!(Rc::strong_count(&counter), 1);
+        }
+    }
+}
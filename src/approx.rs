@@ -0,0 +1,415 @@
+//! Polynomial approximations of transcendental functions, and slice routines that apply
+//! them element-wise.
+//!
+//! The approximations have no branches and no table lookups, so that loops over them
+//! vectorize. They are accurate to about single precision (relative error around `1e-7`)
+//! for both `f32` and `f64`; the `f64` versions are not more accurate, only wider.
+//!
+//! Inputs are clamped so that the results are always finite, which keeps them valid in
+//! [`Fast`].
+//!
+//! ```
+//! use fast_floats::{approx, Fast};
+//!
+//! let f = |x| unsafe { Fast::new(x) };
+//! let mut xs = [f(0.), f(1.), f(-2.)];
+//! approx::apply_exp(&mut xs);
+//! assert!((xs[1].get() - 1f64.exp()).abs() < 1e-6);
+//! ```
+
+use std::f64::consts::{FRAC_1_SQRT_2, FRAC_2_PI, FRAC_2_SQRT_PI, FRAC_PI_2, FRAC_PI_6, LOG2_E, PI};
+
+use crate::{Fast, FastFloat};
+use crate::slice::map_inplace;
+
+// ln 2 split so that `k * LN2_HI` is exact for the exponents in range
+const LN2_HI: f64 = 0.693359375;
+const LN2_LO: f64 = -2.1219444005469058e-4;
+// π/2 split so that `k * FRAC_PI_2_HI` is exact for moderate k
+const FRAC_PI_2_HI: f64 = 1.5703125;
+const FRAC_PI_2_LO: f64 = 4.8382679489661923e-4;
+// tan(π/12), and √3
+const TAN_PI_12: f64 = 0.2679491924311227;
+const SQRT_3: f64 = 1.7320508075688772;
+
+// beyond this, tanh(x) rounds to ±1 at the accuracy of the approximation
+const TANH_MAX_ARG: f64 = 20.;
+// below this, sinh and tanh use their Taylor series
+const SERIES_ARG: f64 = 0.3;
+
+#[inline(always)]
+fn constant<F: FastFloat>(value: f64) -> Fast<F> { Fast(F::from_f64(value)) }
+
+/// Evaluate the polynomial with coefficients `coef` (highest degree first) at `x`
+#[inline(always)]
+fn horner<F: FastFloat>(x: Fast<F>, coef: &[f64]) -> Fast<F> {
+    coef.iter().fold(Fast(F::ZERO), |acc, &c| acc * x + F::from_f64(c))
+}
+
+/// Return `e^x`.
+///
+/// `x` is clamped to the range where the result is finite and normal, so the result is
+/// never zero or infinite.
+#[inline(always)]
+pub fn exp<F: FastFloat>(x: Fast<F>) -> Fast<F> {
+    let x = x.clamp(Fast(F::EXP_MIN_ARG), Fast(F::EXP_MAX_ARG));
+    // x = n ln 2 + r with |r| <= ln 2 / 2
+    let n = Fast((x * F::from_f64(LOG2_E)).0.round_ties_even());
+    let r = x - n * F::from_f64(LN2_HI) - n * F::from_f64(LN2_LO);
+    let p = horner(r, &[1. / 5040., 1. / 720., 1. / 120., 1. / 24., 1. / 6., 0.5, 1., 1.]);
+    // 2^n itself can be out of range at the ends, so scale in two steps
+    let n = n.0.to_i32();
+    p * F::exp2i(n >> 1) * F::exp2i(n - (n >> 1))
+}
+
+/// Return the natural logarithm `ln x`.
+///
+/// Values below the smallest positive normal value, including zero and negative values,
+/// return the logarithm of the smallest positive normal value.
+#[inline(always)]
+pub fn ln<F: FastFloat>(x: Fast<F>) -> Fast<F> {
+    let (m, e) = x.0.max(F::MIN_POSITIVE).frexp_normal();
+    // move m from [0.5, 1) to [√½, √2)
+    let (m, small) = (Fast(m), m < F::from_f64(FRAC_1_SQRT_2));
+    let m = if small { m + m } else { m };
+    let e = constant::<F>((e - small as i32) as f64);
+    // ln m = 2 atanh s = 2 (s + s³/3 + s⁵/5 + ...) where s = (m - 1) / (m + 1), |s| < 0.172
+    let s = (m - F::ONE) / (m + F::ONE);
+    let z = s * s;
+    let p = horner(z, &[1. / 9., 1. / 7., 1. / 5., 1. / 3., 1.]);
+    e * F::from_f64(LN2_HI) + (s * p * F::from_f64(2.) + e * F::from_f64(LN2_LO))
+}
+
+/// Return `x` raised to the power `n`, as `exp(n ln x)`.
+///
+/// `x` is clamped like in [`ln`], so it is treated as positive, and the result is clamped
+/// like in [`exp`]. The relative error grows with the magnitude of `n ln x`.
+#[inline(always)]
+pub fn powf<F: FastFloat>(x: Fast<F>, n: Fast<F>) -> Fast<F> {
+    // strict and clamped, since the product can overflow for a large n
+    let y = F::mul_strict(n.0, ln(x).0);
+    exp(Fast(y.max(F::EXP_MIN_ARG).min(F::EXP_MAX_ARG)))
+}
+
+/// Return the sine and cosine of `x` (in radians), `(sin, cos)`.
+///
+/// The argument is reduced modulo π/2 in working precision, so the accuracy degrades as
+/// `|x|` grows: it is as documented for the module for `|x|` up to about `1e3` (`f32`) or
+/// `1e5` (`f64`). For any `x` the results are finite.
+#[inline(always)]
+pub fn sin_cos<F: FastFloat>(x: Fast<F>) -> (Fast<F>, Fast<F>) {
+    // x = k π/2 + r with |r| <= π/4
+    let k = Fast((x * F::from_f64(FRAC_2_PI)).0.round_ties_even());
+    let r = x - k * F::from_f64(FRAC_PI_2_HI) - k * F::from_f64(FRAC_PI_2_LO);
+    // keep the polynomials bounded even if the reduction was inexact
+    let r = r.clamp(-constant(1.), constant(1.));
+    let z = r * r;
+    let s = r * horner(z, &[1. / 362880., -1. / 5040., 1. / 120., -1. / 6., 1.]);
+    let c = horner(z, &[-1. / 3628800., 1. / 40320., -1. / 720., 1. / 24., -0.5, 1.]);
+    let quadrant = k.0.to_i32();
+    let (s, c) = if quadrant & 1 != 0 { (c, s) } else { (s, c) };
+    let s = if quadrant & 2 != 0 { -s } else { s };
+    let c = if quadrant.wrapping_add(1) & 2 != 0 { -c } else { c };
+    (s, c)
+}
+
+/// Return the sine of `x` (in radians), see [`sin_cos`].
+#[inline(always)]
+pub fn sin<F: FastFloat>(x: Fast<F>) -> Fast<F> { sin_cos(x).0 }
+
+/// Return the cosine of `x` (in radians), see [`sin_cos`].
+#[inline(always)]
+pub fn cos<F: FastFloat>(x: Fast<F>) -> Fast<F> { sin_cos(x).1 }
+
+/// Return the arctangent of `a` for `0 <= a <= 1`
+#[inline(always)]
+fn atan_unit<F: FastFloat>(a: Fast<F>) -> Fast<F> {
+    // atan a = π/6 + atan((√3 a - 1) / (a + √3)), which maps a to |t| <= tan(π/12)
+    let reduce = a.0 > F::from_f64(TAN_PI_12);
+    let t = if reduce { (a * F::from_f64(SQRT_3) - F::ONE) / (a + F::from_f64(SQRT_3)) } else { a };
+    let z = t * t;
+    let p = t * horner(z, &[-1. / 15., 1. / 13., -1. / 11., 1. / 9., -1. / 7., 1. / 5., -1. / 3., 1.]);
+    if reduce { p + F::from_f64(FRAC_PI_6) } else { p }
+}
+
+/// Return the arctangent of `x`, in radians in the range `-π/2..=π/2`.
+#[inline(always)]
+pub fn atan<F: FastFloat>(x: Fast<F>) -> Fast<F> {
+    let a = x.abs();
+    // atan a = π/2 - atan(1/a)
+    let inverted = a.0 > F::ONE;
+    let r = atan_unit(if inverted { constant::<F>(1.) / a } else { a });
+    let r = if inverted { -r + F::from_f64(FRAC_PI_2) } else { r };
+    Fast(r.0.copysign(x.0))
+}
+
+/// Return the four quadrant arctangent of `y` and `x`, in radians in the range `-π..=π`.
+///
+/// If both are zero, the result is zero (with the sign of `y` if `x` is positive).
+#[inline(always)]
+pub fn atan2<F: FastFloat>(y: Fast<F>, x: Fast<F>) -> Fast<F> {
+    let (ay, ax) = (y.abs(), x.abs());
+    let (small, big) = (ay.min(ax), ay.max(ax));
+    let r = atan_unit(small / big.max(Fast(F::MIN_POSITIVE)));
+    let r = if ay > ax { -r + F::from_f64(FRAC_PI_2) } else { r };
+    let r = if x.0 < F::ZERO { -r + F::from_f64(PI) } else { r };
+    Fast(r.0.copysign(y.0))
+}
+
+/// Return the hyperbolic sine `sinh x`.
+///
+/// The result is clamped like in [`exp`], so it is finite.
+#[inline(always)]
+pub fn sinh<F: FastFloat>(x: Fast<F>) -> Fast<F> {
+    let a = x.abs();
+    let e = exp(a);
+    // (e^a - e^-a) / 2 loses precision to cancellation for small a
+    let large = (e - constant::<F>(1.) / e) * F::from_f64(0.5);
+    let small = a * horner(a * a, &[1. / 362880., 1. / 5040., 1. / 120., 1. / 6., 1.]);
+    let s = if a.0 < F::from_f64(SERIES_ARG) { small } else { large };
+    Fast(s.0.copysign(x.0))
+}
+
+/// Return the hyperbolic cosine `cosh x`.
+///
+/// The result is clamped like in [`exp`], so it is finite.
+#[inline(always)]
+pub fn cosh<F: FastFloat>(x: Fast<F>) -> Fast<F> {
+    let e = exp(x.abs());
+    (e + constant::<F>(1.) / e) * F::from_f64(0.5)
+}
+
+/// Return the hyperbolic tangent `tanh x`.
+#[inline(always)]
+pub fn tanh<F: FastFloat>(x: Fast<F>) -> Fast<F> {
+    let a = x.abs().min(constant(TANH_MAX_ARG));
+    // (e^2a - 1) / (e^2a + 1) loses precision to cancellation for small a
+    let e = exp(a + a);
+    let large = (e - F::ONE) / (e + F::ONE);
+    let z = a * a;
+    let small = a * horner(z, &[-1382. / 155925., 62. / 2835., -17. / 315., 2. / 15., -1. / 3., 1.]);
+    let t = if a.0 < F::from_f64(SERIES_ARG) { small } else { large };
+    Fast(t.0.copysign(x.0))
+}
+
+// below this, erf uses its Taylor series
+const ERF_SERIES_ARG: f64 = 0.5;
+// erf is 1 beyond this, and exp(-x²) is clamped
+const ERF_MAX_ARG: f64 = 30.;
+
+/// Return `(erf a, erfc a)` for `a >= 0`, where the first is accurate for small `a` and the
+/// second for large `a`.
+#[inline(always)]
+fn erf_parts<F: FastFloat>(a: Fast<F>) -> (Fast<F>, Fast<F>) {
+    let a = a.min(constant(ERF_MAX_ARG));
+    let z = a * a;
+    let series = a * horner(z, &[-1. / 1320., 1. / 216., -1. / 42., 1. / 10., -1. / 3., 1.])
+        * F::from_f64(FRAC_2_SQRT_PI);
+    // Abramowitz and Stegun 7.1.26
+    let t = constant::<F>(1.) / (a * F::from_f64(0.3275911) + F::ONE);
+    let tail = t * horner(t, &[1.061405429, -1.453152027, 1.421413741, -0.284496736, 0.254829592])
+        * exp(-z);
+    if a.0 < F::from_f64(ERF_SERIES_ARG) {
+        (series, -series + F::ONE)
+    } else {
+        (-tail + F::ONE, tail)
+    }
+}
+
+/// Return the error function `erf x`.
+///
+/// The absolute error is at most about `1.5e-7`; the relative error is also that small
+/// near zero.
+#[inline(always)]
+pub fn erf<F: FastFloat>(x: Fast<F>) -> Fast<F> {
+    let (erf, _) = erf_parts(x.abs());
+    Fast(erf.0.copysign(x.0))
+}
+
+/// Return the complementary error function `erfc x = 1 - erf x`.
+///
+/// The absolute error is at most about `1.5e-7`. For large positive `x` the result is
+/// computed directly instead of as `1 - erf x`, but the relative error grows in the tail;
+/// the result is clamped like in [`exp`], so it is never zero.
+#[inline(always)]
+pub fn erfc<F: FastFloat>(x: Fast<F>) -> Fast<F> {
+    let (_, erfc) = erf_parts(x.abs());
+    if x.0 < F::ZERO { -erfc + F::from_f64(2.) } else { erfc }
+}
+
+// Lanczos approximation with g = 7 and 9 terms
+const LANCZOS_G: f64 = 7.;
+const LANCZOS: [f64; 9] = [
+    0.9999999999998099, 676.5203681218851, -1259.1392167224028, 771.3234287776531,
+    -176.6150291621406, 12.507343278686905, -0.13857109526572012, 9.984369578019572e-6,
+    1.5056327351493116e-7,
+];
+// ln √(2π)
+const LN_SQRT_2PI: f64 = 0.9189385332046728;
+// gamma and its logarithm treat smaller arguments as this
+const GAMMA_MIN_ARG: f64 = 1e-20;
+
+/// Return the logarithm of the gamma function, `ln Γ(x)`, for positive `x`.
+///
+/// Uses the Lanczos approximation, and `ln Γ(x) = ln Γ(x + 1) - ln x` below `0.5`. The
+/// absolute error is about `1e-7 · (1 + |ln Γ(x)|)`. Values below `1e-20`, including zero
+/// and negative values, are treated as `1e-20`. Values above `MAX / 1024` of the float type
+/// are treated as `MAX / 1024`, where the result is already close to `MAX`, so that it stays
+/// finite.
+#[inline(always)]
+pub fn ln_gamma<F: FastFloat>(x: Fast<F>) -> Fast<F> {
+    // (x + 0.5) ln t, where ln t < 1024, does not overflow
+    let x = x.clamp(constant(GAMMA_MIN_ARG), Fast(F::MAX) * F::from_f64(1. / 1024.));
+    if x.0 < F::from_f64(0.5) {
+        lanczos_ln_gamma(x + F::ONE) - ln(x)
+    } else {
+        lanczos_ln_gamma(x)
+    }
+}
+
+// ln Γ(x) for x >= 0.5, where the Lanczos series has no poles
+#[inline(always)]
+fn lanczos_ln_gamma<F: FastFloat>(x: Fast<F>) -> Fast<F> {
+    let x = x - F::ONE;
+    let (first, rest) = (LANCZOS[0], &LANCZOS[1..]);
+    let mut a = constant::<F>(first);
+    let mut i = constant::<F>(1.);
+    for &c in rest {
+        a += constant::<F>(c) / (x + i);
+        i += F::ONE;
+    }
+    let t = x + F::from_f64(LANCZOS_G + 0.5);
+    (x + F::from_f64(0.5)) * ln(t) - t + ln(a) + F::from_f64(LN_SQRT_2PI)
+}
+
+/// Return the gamma function `Γ(x)` for positive `x`, as `exp(ln Γ(x))`.
+///
+/// The relative error is about `1e-7 · (1 + |ln Γ(x)|)`, so it grows for large `x`: it is
+/// about `1e-5` near the largest `x` where the result is finite, about 35 (`f32`) or 171
+/// (`f64`). Beyond that the result is clamped like in [`exp`]. Small values are treated like
+/// in [`ln_gamma`].
+#[inline(always)]
+pub fn gamma<F: FastFloat>(x: Fast<F>) -> Fast<F> {
+    exp(ln_gamma(x))
+}
+
+macro_rules! apply {
+    ($($name:ident, $f:ident;)*) => {
+        $(
+        #[doc = concat!("Replace each element `x` of `xs` with [`", stringify!($f), "(x)`](", stringify!($f), ").")]
+        pub fn $name<F: FastFloat>(xs: &mut [Fast<F>]) {
+            map_inplace(xs, $f)
+        }
+        )*
+    }
+}
+
+apply! {
+    apply_exp, exp;
+    apply_ln, ln;
+    apply_sinh, sinh;
+    apply_cosh, cosh;
+    apply_tanh, tanh;
+    apply_sin, sin;
+    apply_cos, cos;
+    apply_erf, erf;
+    apply_erfc, erfc;
+    apply_ln_gamma, ln_gamma;
+    apply_gamma, gamma;
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate std as libstd;
+
+    use super::*;
+
+    fn assert_rel(a: Fast<f64>, b: f64, tol: f64) {
+        assert!((a.get() - b).abs() <= tol * b.abs(), "{:?} != {}", a, b);
+    }
+
+    #[test]
+    fn accuracy() {
+        for i in -2000..2000 {
+            let x = i as f64 / 20.;
+            assert_rel(exp(Fast(x)), x.exp(), 2e-7);
+            assert_rel(tanh(Fast(x / 10.)), (x / 10.).tanh(), 2e-7);
+            assert_rel(sinh(Fast(x / 10.)), (x / 10.).sinh(), 2e-7);
+            assert_rel(cosh(Fast(x / 10.)), (x / 10.).cosh(), 2e-7);
+            let y = (x / 5.).exp();
+            assert!((ln(Fast(y)).get() - x / 5.).abs() <= 2e-7 * (x / 5.).abs().max(1.));
+        }
+        assert!((exp(Fast(1e3f32)).get()).is_finite());
+        assert!(exp(Fast(-1e3)).get() > 0.);
+        for i in -5000..5000 {
+            let x = i as f64 / 50.;
+            let (s, c) = sin_cos(Fast(x));
+            assert!((s.get() - x.sin()).abs() < 2e-7 && (c.get() - x.cos()).abs() < 2e-7, "{}", x);
+        }
+        assert!(sin(Fast(1e30f32)).get().abs() <= 1.);
+        for i in -1000..1000 {
+            let x = i as f64 / 10.;
+            assert!((atan(Fast(x)).get() - x.atan()).abs() < 2e-7, "{}", x);
+            let (y, x) = ((i % 37) as f64, (i / 37) as f64);
+            assert!((atan2(Fast(y), Fast(x)).get() - y.atan2(x)).abs() < 2e-7, "{} {}", y, x);
+        }
+        assert_eq!(ln(Fast(0.)), ln(Fast(f64::MIN_POSITIVE)));
+        assert_rel(powf(Fast(2.), Fast(10.)), 1024., 1e-6);
+        assert_rel(powf(Fast(10.), Fast(-0.5)), 0.1f64.sqrt(), 1e-6);
+        assert!(powf(Fast(10f32), Fast(f32::MAX)).get().is_finite());
+        assert!(powf(Fast(0.5f64), Fast(f64::MAX)).get() > 0.);
+        assert!(powf(Fast(0f64), Fast(-f64::MAX)).get().is_finite());
+        assert_eq!(tanh(Fast(-1e3f32)), Fast(-1.));
+    }
+
+    #[test]
+    fn error_function() {
+        let values: [(f64, f64); 5] = [(0.1, 0.1124629160182849), (0.5, 0.5204998778130465),
+                      (1., 0.8427007929497149), (2., 0.9953222650189527), (4., 0.9999999845827421)];
+        for (x, erf_x) in values {
+            assert!((erf(Fast(x)).get() - erf_x).abs() < 2e-7, "{}", x);
+            assert!((erf(Fast(-x)).get() + erf_x).abs() < 2e-7, "{}", x);
+            assert!((erfc(Fast(x)).get() - (1. - erf_x)).abs() < 2e-7, "{}", x);
+            assert!((erfc(Fast(-x)).get() - (1. + erf_x)).abs() < 2e-7, "{}", x);
+        }
+        assert_rel(erf(Fast(1e-10)), 1.1283791670955126e-10, 1e-7);
+        assert!(erfc(Fast(100f32)).get() > 0.);
+        assert!(erfc(Fast(1e30f32)).get() > 0.);
+        assert!(erfc(Fast(1e200f64)).get() > 0.);
+        assert_eq!(erf(Fast(-1e200f64)), Fast(-1.));
+        assert_eq!(erfc(Fast(-f32::MAX)), Fast(2.));
+    }
+
+    #[test]
+    fn gamma_function() {
+        assert_rel(gamma(Fast(5.)), 24., 1e-6);
+        assert_rel(gamma(Fast(10.)), 362880., 1e-6);
+        assert_rel(gamma(Fast(0.5)), PI.sqrt(), 1e-6);
+        assert_rel(gamma(Fast(0.1)), 9.513507698668732, 1e-6);
+        assert_rel(ln_gamma(Fast(100.)), 359.1342053695754, 1e-7);
+        assert!(ln_gamma(Fast(1f64)).get().abs() < 1e-7);
+        assert_rel(ln_gamma(Fast(0.3)), 1.0957979948180752, 1e-7);
+        assert_rel(ln_gamma(Fast(1e-3)), 6.907178885383854, 1e-7);
+        assert_rel(ln_gamma(Fast(1e-20)), 46.051701859880914, 1e-7);
+        assert_rel(ln_gamma(Fast(0.)), 46.051701859880914, 1e-7);
+        assert!((ln_gamma(Fast(1e-20f32)).get() - 46.0517).abs() < 1e-4);
+        assert!(gamma(Fast(1e3f32)).get().is_finite());
+        assert_rel(ln_gamma(Fast(1e6)), 12815504.569147611, 1e-7);
+        assert!(gamma(Fast(1e38f32)).get().is_finite());
+        assert!(ln_gamma(Fast(f32::MAX)).get().is_finite());
+        assert!(gamma(Fast(f64::MAX)).get().is_finite());
+        assert!(ln_gamma(Fast(f64::MAX)).get() > 1e307);
+    }
+
+    #[test]
+    fn slices() {
+        let mut xs = [Fast(0f32), Fast(1.), Fast(2.)];
+        apply_exp(&mut xs);
+        apply_ln(&mut xs);
+        for (i, x) in xs.iter().enumerate() {
+            assert!((x.get() - i as f32).abs() < 1e-6);
+        }
+        apply_tanh(&mut xs);
+        assert_eq!(xs[0], Fast(0.));
+    }
+}
@@ -0,0 +1,12 @@
+//! Target specific implementations of the slice kernels.
+//!
+//! The functions in these modules are `unsafe` because they require CPU features that
+//! might not be available at runtime. The kernels in [`slice`](crate::slice) use them
+//! automatically when the CPU supports them; see the module for each architecture.
+
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+pub mod x86;
+#[cfg(target_arch = "aarch64")]
+pub mod aarch64;
+#[cfg(target_arch = "wasm32")]
+pub mod wasm32;
@@ -0,0 +1,130 @@
+//! `simd128` implementations of the slice kernels for wasm32.
+//!
+//! The kernels in [`slice`](crate::slice) use these when the `simd128` target feature is
+//! enabled at compile time. `simd128` has no fused multiply-add, so these use a separate
+//! multiply and add.
+
+use std::arch::wasm32::*;
+
+use crate::Fast;
+
+#[inline]
+#[target_feature(enable = "simd128")]
+unsafe fn hsum_f32x4(v: v128) -> f32 {
+    (f32x4_extract_lane::<0>(v) + f32x4_extract_lane::<1>(v))
+        + (f32x4_extract_lane::<2>(v) + f32x4_extract_lane::<3>(v))
+}
+
+#[inline]
+#[target_feature(enable = "simd128")]
+unsafe fn hsum_f64x2(v: v128) -> f64 {
+    f64x2_extract_lane::<0>(v) + f64x2_extract_lane::<1>(v)
+}
+
+macro_rules! wasm_kernels {
+    ($t:ident, $lanes:expr, $sum:ident, $dot:ident, $axpy:ident,
+     $splat:ident, $add:ident, $mul:ident, $hsum:ident) => {
+        /// Return the sum of the elements of `xs`, see [`slice::sum`](crate::slice::sum).
+        ///
+        /// # Safety
+        ///
+        /// The runtime must support `simd128`.
+        #[target_feature(enable = "simd128")]
+        pub unsafe fn $sum(xs: &[Fast<$t>]) -> Fast<$t> {
+            const STEP: usize = 4 * $lanes;
+            // independent accumulators hide the latency of the vector add
+            let mut acc = [$splat(0.); 4];
+            let blocks = xs.chunks_exact(STEP);
+            let rest = blocks.remainder();
+            for b in blocks {
+                for (k, a) in acc.iter_mut().enumerate() {
+                    *a = $add(*a, v128_load(b.as_ptr().add(k * $lanes) as *const v128));
+                }
+            }
+            let v = $add($add(acc[0], acc[1]), $add(acc[2], acc[3]));
+            rest.iter().fold(Fast($hsum(v)), |s, &x| s + x)
+        }
+
+        /// Return the dot product of `xs` and `ys`, see [`slice::dot`](crate::slice::dot).
+        ///
+        /// # Safety
+        ///
+        /// The runtime must support `simd128`. The slices must be of equal length.
+        #[target_feature(enable = "simd128")]
+        pub unsafe fn $dot(xs: &[Fast<$t>], ys: &[Fast<$t>]) -> Fast<$t> {
+            debug_assert_eq!(xs.len(), ys.len());
+            const STEP: usize = 4 * $lanes;
+            let mut acc = [$splat(0.); 4];
+            let xb = xs.chunks_exact(STEP);
+            let yb = ys.chunks_exact(STEP);
+            let rest = xb.remainder().iter().zip(yb.remainder());
+            for (xc, yc) in xb.zip(yb) {
+                for (k, a) in acc.iter_mut().enumerate() {
+                    let x = v128_load(xc.as_ptr().add(k * $lanes) as *const v128);
+                    let y = v128_load(yc.as_ptr().add(k * $lanes) as *const v128);
+                    *a = $add(*a, $mul(x, y));
+                }
+            }
+            let v = $add($add(acc[0], acc[1]), $add(acc[2], acc[3]));
+            rest.fold(Fast($hsum(v)), |s, (&x, &y)| s + x * y)
+        }
+
+        /// Compute `ys[i] = a * xs[i] + ys[i]`, see [`slice::axpy`](crate::slice::axpy).
+        ///
+        /// # Safety
+        ///
+        /// The runtime must support `simd128`. The slices must be of equal length.
+        #[target_feature(enable = "simd128")]
+        pub unsafe fn $axpy(a: Fast<$t>, xs: &[Fast<$t>], ys: &mut [Fast<$t>]) {
+            debug_assert_eq!(xs.len(), ys.len());
+            let av = $splat(a.0);
+            let mut xb = xs.chunks_exact($lanes);
+            let mut yb = ys.chunks_exact_mut($lanes);
+            for (xc, yc) in (&mut xb).zip(&mut yb) {
+                let x = v128_load(xc.as_ptr() as *const v128);
+                let y = v128_load(yc.as_ptr() as *const v128);
+                v128_store(yc.as_mut_ptr() as *mut v128, $add($mul(av, x), y));
+            }
+            for (y, &x) in yb.into_remainder().iter_mut().zip(xb.remainder()) {
+                *y = a * x + *y;
+            }
+        }
+    }
+}
+
+wasm_kernels!(f32, 4, sum_f32, dot_f32, axpy_f32, f32x4_splat, f32x4_add, f32x4_mul, hsum_f32x4);
+wasm_kernels!(f64, 2, sum_f64, dot_f64, axpy_f64, f64x2_splat, f64x2_add, f64x2_mul, hsum_f64x2);
+
+#[cfg(all(test, target_feature = "simd128"))]
+mod tests {
+    use super::*;
+    use crate::slice::portable;
+
+    #[test]
+    fn match_portable() {
+        let xs: [Fast<f32>; 75] = core::array::from_fn(|i| Fast(i as f32));
+        let ys: [Fast<f32>; 75] = core::array::from_fn(|i| Fast(2. - i as f32 / 2.));
+        for n in 0..xs.len() {
+            let (xs, ys) = (&xs[n..], &ys[n..]);
+            unsafe {
+                assert_eq!(sum_f32(xs), portable::sum(xs));
+                assert_eq!(dot_f32(xs, ys), portable::dot(xs, ys));
+                let (mut a, mut b) = ([Fast(0.); 75], [Fast(0.); 75]);
+                let (a, b) = (&mut a[n..], &mut b[n..]);
+                a.copy_from_slice(ys);
+                b.copy_from_slice(ys);
+                axpy_f32(Fast(0.5), xs, a);
+                portable::axpy(Fast(0.5), xs, b);
+                assert_eq!(a, b);
+            }
+        }
+        let xs: [Fast<f64>; 21] = core::array::from_fn(|i| Fast(i as f64 - 7.));
+        for n in 0..xs.len() {
+            let xs = &xs[n..];
+            unsafe {
+                assert_eq!(sum_f64(xs), portable::sum(xs));
+                assert_eq!(dot_f64(xs, xs), portable::dot(xs, xs));
+            }
+        }
+    }
+}
@@ -0,0 +1,200 @@
+//! AVX and FMA implementations of the slice kernels for x86 and x86_64.
+//!
+//! The kernels in [`slice`](crate::slice) detect AVX and FMA support at runtime (or at
+//! compile time, if the features are enabled globally) and use the functions in this module
+//! when possible. They can also be called directly; use [`is_avx_fma_supported`] to check
+//! if that is allowed.
+
+#[cfg(target_arch = "x86")]
+use std::arch::x86::*;
+#[cfg(target_arch = "x86_64")]
+use std::arch::x86_64::*;
+use std::sync::atomic::{AtomicU8, Ordering};
+
+use crate::Fast;
+use crate::slice::split_aligned;
+
+/// Return `true` if the CPU supports AVX and FMA and the operating system has enabled the
+/// AVX register state, so that the functions in this module may be called.
+///
+/// The detection uses `cpuid` and is cached after the first call. It needs no runtime
+/// checks at all if the crate is compiled with the `avx` and `fma` target features enabled.
+#[inline]
+pub fn is_avx_fma_supported() -> bool {
+    if cfg!(all(target_feature = "avx", target_feature = "fma")) {
+        return true;
+    }
+    // 0: unknown, 1: not supported, 2: supported
+    static DETECTED: AtomicU8 = AtomicU8::new(0);
+    match DETECTED.load(Ordering::Relaxed) {
+        0 => {
+            let supported = detect_avx_fma();
+            DETECTED.store(1 + supported as u8, Ordering::Relaxed);
+            supported
+        }
+        state => state == 2,
+    }
+}
+
+#[cold]
+fn detect_avx_fma() -> bool {
+    const FMA: u32 = 1 << 12;
+    const OSXSAVE: u32 = 1 << 27;
+    const AVX: u32 = 1 << 28;
+    let required = FMA | OSXSAVE | AVX;
+    if __cpuid(1).ecx & required != required {
+        return false;
+    }
+    // XCR0 bits 1 and 2: the OS saves and restores SSE and AVX registers
+    unsafe { xcr0() & 0b110 == 0b110 }
+}
+
+#[target_feature(enable = "xsave")]
+unsafe fn xcr0() -> u64 { _xgetbv(0) }
+
+/// Sum the lanes of `v`
+#[inline]
+#[target_feature(enable = "avx")]
+unsafe fn hsum_ps(v: __m256) -> f32 {
+    let s = _mm_add_ps(_mm256_castps256_ps128(v), _mm256_extractf128_ps(v, 1));
+    let s = _mm_add_ps(s, _mm_movehl_ps(s, s));
+    let s = _mm_add_ss(s, _mm_shuffle_ps(s, s, 1));
+    _mm_cvtss_f32(s)
+}
+
+/// Sum the lanes of `v`
+#[inline]
+#[target_feature(enable = "avx")]
+unsafe fn hsum_pd(v: __m256d) -> f64 {
+    let s = _mm_add_pd(_mm256_castpd256_pd128(v), _mm256_extractf128_pd(v, 1));
+    let s = _mm_add_sd(s, _mm_unpackhi_pd(s, s));
+    _mm_cvtsd_f64(s)
+}
+
+macro_rules! x86_kernels {
+    ($t:ident, $lanes:expr, $sum:ident, $dot:ident, $axpy:ident, $hsum:ident,
+     $setzero:ident, $set1:ident, $load:ident, $loadu:ident, $storeu:ident,
+     $add:ident, $fmadd:ident) => {
+        /// Return the sum of the elements of `xs`, see [`slice::sum`](crate::slice::sum).
+        ///
+        /// # Safety
+        ///
+        /// The CPU must support AVX; calling this function on a CPU that does not is
+        /// undefined behavior. It is always safe to call if [`is_avx_fma_supported`] returns
+        /// `true`.
+        #[target_feature(enable = "avx")]
+        pub unsafe fn $sum(xs: &[Fast<$t>]) -> Fast<$t> {
+            let (head, body, tail) = split_aligned::<$t, $lanes>(xs);
+            // independent accumulators hide the latency of the vector add
+            let mut acc = [$setzero(); 4];
+            let mut blocks = body.chunks_exact(4);
+            for chunk in &mut blocks {
+                for (a, b) in acc.iter_mut().zip(chunk) {
+                    *a = $add(*a, $load(b.as_ptr() as *const $t));
+                }
+            }
+            for b in blocks.remainder() {
+                acc[0] = $add(acc[0], $load(b.as_ptr() as *const $t));
+            }
+            let v = $add($add(acc[0], acc[1]), $add(acc[2], acc[3]));
+            head.iter().chain(tail).fold(Fast($hsum(v)), |s, &x| s + x)
+        }
+
+        /// Return the dot product of `xs` and `ys`, see [`slice::dot`](crate::slice::dot).
+        ///
+        /// # Safety
+        ///
+        /// The CPU must support AVX and FMA; calling this function on a CPU that does not is
+        /// undefined behavior. It is always safe to call if [`is_avx_fma_supported`] returns
+        /// `true`.
+        ///
+        /// The slices must be of equal length.
+        #[target_feature(enable = "avx,fma")]
+        pub unsafe fn $dot(xs: &[Fast<$t>], ys: &[Fast<$t>]) -> Fast<$t> {
+            debug_assert_eq!(xs.len(), ys.len());
+            const STEP: usize = 4 * $lanes;
+            let mut acc = [$setzero(); 4];
+            let xb = xs.chunks_exact(STEP);
+            let yb = ys.chunks_exact(STEP);
+            let rest = xb.remainder().iter().zip(yb.remainder());
+            for (xc, yc) in xb.zip(yb) {
+                for (k, a) in acc.iter_mut().enumerate() {
+                    let x = $loadu(xc.as_ptr().add(k * $lanes) as *const $t);
+                    let y = $loadu(yc.as_ptr().add(k * $lanes) as *const $t);
+                    *a = $fmadd(x, y, *a);
+                }
+            }
+            let v = $add($add(acc[0], acc[1]), $add(acc[2], acc[3]));
+            rest.fold(Fast($hsum(v)), |s, (&x, &y)| s + x * y)
+        }
+
+        /// Compute `ys[i] = a * xs[i] + ys[i]`, see [`slice::axpy`](crate::slice::axpy).
+        ///
+        /// # Safety
+        ///
+        /// The CPU must support AVX and FMA; calling this function on a CPU that does not is
+        /// undefined behavior. It is always safe to call if [`is_avx_fma_supported`] returns
+        /// `true`.
+        ///
+        /// The slices must be of equal length.
+        #[target_feature(enable = "avx,fma")]
+        pub unsafe fn $axpy(a: Fast<$t>, xs: &[Fast<$t>], ys: &mut [Fast<$t>]) {
+            debug_assert_eq!(xs.len(), ys.len());
+            let av = $set1(a.0);
+            let mut xb = xs.chunks_exact($lanes);
+            let mut yb = ys.chunks_exact_mut($lanes);
+            for (xc, yc) in (&mut xb).zip(&mut yb) {
+                let x = $loadu(xc.as_ptr() as *const $t);
+                let y = $loadu(yc.as_ptr() as *const $t);
+                $storeu(yc.as_mut_ptr() as *mut $t, $fmadd(av, x, y));
+            }
+            for (y, &x) in yb.into_remainder().iter_mut().zip(xb.remainder()) {
+                *y = a * x + *y;
+            }
+        }
+    }
+}
+
+x86_kernels!(f32, 8, sum_f32, dot_f32, axpy_f32, hsum_ps,
+             _mm256_setzero_ps, _mm256_set1_ps, _mm256_load_ps, _mm256_loadu_ps, _mm256_storeu_ps,
+             _mm256_add_ps, _mm256_fmadd_ps);
+x86_kernels!(f64, 4, sum_f64, dot_f64, axpy_f64, hsum_pd,
+             _mm256_setzero_pd, _mm256_set1_pd, _mm256_load_pd, _mm256_loadu_pd, _mm256_storeu_pd,
+             _mm256_add_pd, _mm256_fmadd_pd);
+
+#[cfg(test)]
+mod tests {
+    extern crate std as libstd;
+
+    use super::*;
+    use crate::slice::portable;
+
+    #[test]
+    fn detect() {
+        assert_eq!(is_avx_fma_supported(),
+                   libstd::is_x86_feature_detected!("avx") && libstd::is_x86_feature_detected!("fma"));
+    }
+
+    #[test]
+    fn match_portable() {
+        if !is_avx_fma_supported() {
+            return;
+        }
+        let xs: [Fast<f32>; 75] = core::array::from_fn(|i| Fast(i as f32));
+        let ys: [Fast<f32>; 75] = core::array::from_fn(|i| Fast(2. - i as f32 / 2.));
+        for n in 0..xs.len() {
+            let (xs, ys) = (&xs[n..], &ys[n..]);
+            unsafe {
+                assert_eq!(sum_f32(xs), portable::sum(xs));
+                assert_eq!(dot_f32(xs, ys), portable::dot(xs, ys));
+                let (mut a, mut b) = ([Fast(0.); 75], [Fast(0.); 75]);
+                let (a, b) = (&mut a[n..], &mut b[n..]);
+                a.copy_from_slice(ys);
+                b.copy_from_slice(ys);
+                axpy_f32(Fast(0.5), xs, a);
+                portable::axpy(Fast(0.5), xs, b);
+                assert_eq!(a, b);
+            }
+        }
+    }
+}
@@ -0,0 +1,90 @@
+//! Element-wise arithmetic for arrays `[Fast<F>; N]`.
+//!
+//! The operators are implemented between an array and a scalar `Fast<F>`, in both orders,
+//! with the compound assignments on the array. The coherence rules do not allow operator
+//! implementations between two arrays in this crate, since arrays are foreign types; the
+//! functions [`add`], [`sub`], [`mul`] and [`div`] take their place.
+//!
+//! ```
+//! use fast_floats::{array, Fast};
+//!
+//! let f = |x| unsafe { Fast::new(x) };
+//! let rgb = [0.25, 0.5, 1.].map(f);
+//! let tinted = array::mul(rgb, [1., 0.5, 0.5].map(f)) * f(2.);
+//! assert_eq!(tinted, [0.5, 0.5, 1.].map(f));
+//! ```
+
+use std::ops::{Add, AddAssign, Div, DivAssign, Mul, MulAssign, Sub, SubAssign};
+
+use crate::{Fast, FastFloat};
+
+macro_rules! impl_array_op {
+    ($($name:ident, $method:ident, $assign:ident, $assign_method:ident, $doc:expr;)*) => {
+        $(
+        #[doc = $doc]
+        #[inline]
+        pub fn $method<F: FastFloat, const N: usize>(a: [Fast<F>; N], b: [Fast<F>; N])
+            -> [Fast<F>; N]
+        {
+            let mut out = a;
+            for (x, y) in out.iter_mut().zip(b) {
+                *x = x.$method(y);
+            }
+            out
+        }
+
+        // [Fast<F>; N] + Fast<F>
+        impl<F: FastFloat, const N: usize> $name<Fast<F>> for [Fast<F>; N] {
+            type Output = Self;
+            #[inline]
+            fn $method(self, rhs: Fast<F>) -> Self { self.map(|x| x.$method(rhs)) }
+        }
+
+        // Fast<F> + [Fast<F>; N]
+        impl<F: FastFloat, const N: usize> $name<[Fast<F>; N]> for Fast<F> {
+            type Output = [Fast<F>; N];
+            #[inline]
+            fn $method(self, rhs: [Fast<F>; N]) -> [Fast<F>; N] { rhs.map(|x| self.$method(x)) }
+        }
+
+        // [Fast<F>; N] += Fast<F>
+        impl<F: FastFloat, const N: usize> $assign<Fast<F>> for [Fast<F>; N] {
+            #[inline]
+            fn $assign_method(&mut self, rhs: Fast<F>) {
+                for x in self {
+                    *x = x.$method(rhs);
+                }
+            }
+        }
+        )*
+    }
+}
+
+impl_array_op! {
+    Add, add, AddAssign, add_assign, "Return the element-wise sum `a[i] + b[i]`";
+    Sub, sub, SubAssign, sub_assign, "Return the element-wise difference `a[i] - b[i]`";
+    Mul, mul, MulAssign, mul_assign, "Return the element-wise product `a[i] * b[i]`";
+    Div, div, DivAssign, div_assign, "Return the element-wise quotient `a[i] / b[i]`";
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ops() {
+        let a = [Fast(1f32), Fast(2.), Fast(4.)];
+        let b = [Fast(4f32), Fast(2.), Fast(1.)];
+        assert_eq!(add(a, b), [Fast(5.), Fast(4.), Fast(5.)]);
+        assert_eq!(sub(a, b), [Fast(-3.), Fast(0.), Fast(3.)]);
+        assert_eq!(mul(a, b), [Fast(4.), Fast(4.), Fast(4.)]);
+        assert_eq!(div(a, b), [Fast(0.25), Fast(1.), Fast(4.)]);
+        assert_eq!(a * Fast(2.), [Fast(2.), Fast(4.), Fast(8.)]);
+        assert_eq!(Fast(8.) / a, b.map(|x| x + x));
+        assert_eq!(Fast(1.) - a, [Fast(0.), Fast(-1.), Fast(-3.)]);
+        let mut c = a;
+        c += Fast(1.);
+        c /= Fast(2.);
+        assert_eq!(c, [Fast(1.), Fast(1.5), Fast(2.5)]);
+    }
+}
@@ -0,0 +1,271 @@
+//! Measure how sensitive a reduction is to the order of its operations.
+//!
+//! The fast-math flags let the compiler associate a sum in any order, and the order that is
+//! chosen can change with the target, the unroll factor or the compiler version. The
+//! functions here evaluate a reduction in several orders and report the spread of the
+//! results, together with a compensated reference sum:
+//!
+//! - in sequence, forwards and backwards
+//! - in 2, 4, 8, 16 and 32 interleaved lanes, combined pairwise
+//! - pairwise, by recursive halving
+//! - in blocks of 64 and 4096 elements, as [`slice::sum_blocked`]
+//! - with the fast kernel of the crate, such as [`slice::sum`]
+//!
+//! [`sum_condition_number`] bounds the error of a sum without evaluating the orders, and
+//! [`recommend_sum`] uses the bound to choose between fast, pairwise and compensated
+//! summation.
+//!
+//! ```
+//! use fast_floats::{audit, Fast};
+//!
+//! let f = |x| unsafe { Fast::new(x) };
+//! // a sum with cancellation is order sensitive
+//! let xs = [1e16, 1., -1e16, 1.].map(f);
+//! let spread = audit::sum_spread(&xs);
+//! assert_eq!(spread.reference.get(), 2.);
+//! assert!(spread.width().get() > 0.);
+//! ```
+
+use crate::eft;
+use crate::slice;
+use crate::{Fast, FastFloat};
+
+/// The spread of the results of a reduction evaluated in several orders.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Spread<F> {
+    /// The smallest result
+    pub min: Fast<F>,
+    /// The largest result
+    pub max: Fast<F>,
+    /// The result of a compensated (Neumaier) summation, which is accurate independently
+    /// of the order unless the sum has catastrophic cancellation
+    pub reference: Fast<F>,
+    /// The number of orders that were evaluated
+    pub orderings: usize,
+}
+
+impl<F: FastFloat> Spread<F> {
+    /// Return the difference between the largest and the smallest result
+    pub fn width(&self) -> Fast<F> { self.max - self.min }
+
+    /// Return the distance in ULPs between the largest and the smallest result
+    pub fn ulps(&self) -> u64 { self.min.ulps_between(self.max) }
+
+    /// Return the largest absolute difference between a result and the reference
+    pub fn max_error(&self) -> Fast<F> {
+        (self.max - self.reference).abs().max((self.min - self.reference).abs())
+    }
+
+    /// Return the largest error relative to the magnitude of the reference, or zero if the
+    /// reference and the error are zero.
+    ///
+    /// If the reference is zero and the error is not, this is the error relative to the
+    /// smallest positive normal value.
+    pub fn relative_error(&self) -> Fast<F> {
+        let error = self.max_error();
+        if error == Fast(F::ZERO) {
+            return error;
+        }
+        error / self.reference.abs().max(Fast(F::MIN_POSITIVE))
+    }
+
+    fn record(&mut self, x: F) {
+        let x = Fast(x);
+        self.min = self.min.min(x);
+        self.max = self.max.max(x);
+        self.orderings += 1;
+    }
+}
+
+/// Evaluate the sum of `xs` in several orders, see the module documentation.
+pub fn sum_spread<F: FastFloat>(xs: &[Fast<F>]) -> Spread<F> {
+    spread(xs.len(), |i| xs[i].0, sum_compensated(xs).0, slice::sum(xs))
+}
+
+/// Evaluate the dot product of `xs` and `ys` in several orders, see the module
+/// documentation.
+///
+/// The products are rounded before they are summed, except in the fast kernel
+/// [`slice::dot`] which can use FMA, and in the reference, which adds the rounding error of
+/// each product.
+///
+/// ***Panics*** if the slices are not of equal length.
+pub fn dot_spread<F: FastFloat>(xs: &[Fast<F>], ys: &[Fast<F>]) -> Spread<F> {
+    assert_eq!(xs.len(), ys.len(), "dot_spread: slices must be of equal length");
+    let mut reference = Neumaier::new();
+    for (x, y) in xs.iter().zip(ys) {
+        let (p, e) = eft::two_prod(*x, *y);
+        reference.add(p.0);
+        reference.add(e.0);
+    }
+    let term = |i: usize| F::mul_strict(xs[i].0, ys[i].0);
+    spread(xs.len(), term, reference.finish(), slice::dot(xs, ys))
+}
+
+/// Return the sum of `xs` with compensated (Neumaier) summation, the reference of
+/// [`sum_spread`].
+///
+/// The error is at most about one rounding of the result plus `n² u²` times the
+/// [condition number](sum_condition_number), where `u` is half the machine epsilon.
+pub fn sum_compensated<F: FastFloat>(xs: &[Fast<F>]) -> Fast<F> {
+    let mut sum = Neumaier::new();
+    for x in xs {
+        sum.add(x.0);
+    }
+    Fast(sum.finish())
+}
+
+/// Return the condition number of the sum of `xs`, `Σ|xᵢ| / |Σxᵢ|`.
+///
+/// The relative error of a sum of `n` terms, in any order, is at most about `(n - 1) u`
+/// times the condition number, where `u` is half the machine epsilon; pairwise summation
+/// reduces the factor to `log₂ n`. A sum of terms of one sign has condition number one.
+///
+/// Return `None` if the sum cancels to zero, or if the condition number is too large to
+/// represent.
+pub fn sum_condition_number<F: FastFloat>(xs: &[Fast<F>]) -> Option<Fast<F>> {
+    // in plain f64 arithmetic, which may overflow to infinity
+    let abs_sum = xs.iter().fold(0., |acc, x| acc + x.0.abs().to_f64());
+    let condition = F::from_f64(abs_sum / sum_compensated(xs).0.abs().to_f64());
+    if condition.is_finite() { Some(Fast(condition)) } else { None }
+}
+
+/// A summation method, recommended by [`recommend_sum`].
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum SumMethod {
+    /// The fast kernel, [`slice::sum`]
+    Fast,
+    /// Pairwise summation, such as [`TreeSum`](crate::accum::TreeSum)
+    Pairwise,
+    /// Compensated summation, [`sum_compensated`]
+    Compensated,
+}
+
+/// Recommend the fastest summation method for `xs` whose error bound is within the
+/// relative error `tolerance`.
+///
+/// The bounds are the ones of [`sum_condition_number`]; they are worst cases, and typical
+/// errors are closer to their square root. If the sum cancels to zero, the recommendation
+/// is the compensated sum.
+pub fn recommend_sum<F: FastFloat>(xs: &[Fast<F>], tolerance: Fast<F>) -> SumMethod {
+    let Some(condition) = sum_condition_number(xs) else { return SumMethod::Compensated };
+    let n = xs.len();
+    let bound = condition.0.to_f64() * F::EPSILON.to_f64() / 2.;
+    let tolerance = tolerance.0.to_f64();
+    if n.saturating_sub(1) as f64 * bound <= tolerance {
+        SumMethod::Fast
+    } else if n.next_power_of_two().trailing_zeros() as f64 * bound <= tolerance {
+        SumMethod::Pairwise
+    } else {
+        SumMethod::Compensated
+    }
+}
+
+fn spread<F: FastFloat>(n: usize, term: impl Fn(usize) -> F, reference: F, kernel: Fast<F>)
+    -> Spread<F>
+{
+    let mut spread = Spread { min: kernel, max: kernel, reference: Fast(reference), orderings: 1 };
+    spread.record((0..n).fold(F::ZERO, |acc, i| F::add_strict(acc, term(i))));
+    spread.record((0..n).rev().fold(F::ZERO, |acc, i| F::add_strict(acc, term(i))));
+    spread.record(lanes::<F, 2>(0..n, &term));
+    spread.record(lanes::<F, 4>(0..n, &term));
+    spread.record(lanes::<F, 8>(0..n, &term));
+    spread.record(lanes::<F, 16>(0..n, &term));
+    spread.record(lanes::<F, 32>(0..n, &term));
+    spread.record(pairwise(0, n, &term));
+    for block in [64, 4096] {
+        let blocks = (0..n).step_by(block);
+        spread.record(blocks.fold(F::ZERO, |acc, start| {
+            F::add_strict(acc, lanes::<F, 8>(start..n.min(start + block), &term))
+        }));
+    }
+    spread
+}
+
+/// Sum the terms in `L` interleaved lanes, and combine the lanes pairwise
+fn lanes<F: FastFloat, const L: usize>(range: std::ops::Range<usize>, term: &impl Fn(usize) -> F)
+    -> F
+{
+    let mut lanes = [F::ZERO; L];
+    for (k, i) in range.enumerate() {
+        lanes[k % L] = F::add_strict(lanes[k % L], term(i));
+    }
+    let mut n = L;
+    while n > 1 {
+        n /= 2;
+        for i in 0..n {
+            lanes[i] = F::add_strict(lanes[i], lanes[i + n]);
+        }
+    }
+    lanes[0]
+}
+
+fn pairwise<F: FastFloat>(start: usize, end: usize, term: &impl Fn(usize) -> F) -> F {
+    if end - start <= 8 {
+        return (start..end).fold(F::ZERO, |acc, i| F::add_strict(acc, term(i)));
+    }
+    let mid = start + (end - start) / 2;
+    F::add_strict(pairwise(start, mid, term), pairwise(mid, end, term))
+}
+
+/// Neumaier's variant of Kahan summation, with the error of each addition from TwoSum
+struct Neumaier<F> {
+    sum: F,
+    compensation: F,
+}
+
+impl<F: FastFloat> Neumaier<F> {
+    fn new() -> Self { Neumaier { sum: F::ZERO, compensation: F::ZERO } }
+
+    fn add(&mut self, x: F) {
+        let (sum, error) = eft::two_sum(Fast(self.sum), Fast(x));
+        self.compensation = F::add_strict(self.compensation, error.0);
+        self.sum = sum.0;
+    }
+
+    fn finish(&self) -> F { F::add_strict(self.sum, self.compensation) }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn spreads() {
+        let xs = [Fast(1f64), Fast(2.), Fast(3.)];
+        let spread = sum_spread(&xs);
+        assert_eq!(spread, Spread { min: Fast(6.), max: Fast(6.), reference: Fast(6.),
+                                    orderings: 11 });
+        assert_eq!((spread.width(), spread.ulps()), (Fast(0.), 0));
+        assert_eq!(spread.relative_error(), Fast(0.));
+
+        let xs = [Fast(1e16), Fast(1.), Fast(-1e16), Fast(1.)];
+        let spread = sum_spread(&xs);
+        assert_eq!(spread.reference, Fast(2.));
+        assert_eq!((spread.min, spread.max), (Fast(0.), Fast(2.)));
+        assert_eq!(spread.relative_error(), Fast(1.));
+
+        let ys = [Fast(1.), Fast(1.), Fast(1.), Fast(1.)];
+        assert_eq!(dot_spread(&xs, &ys).reference, Fast(2.));
+        // the product 0.1 * 0.1 is not exact, but the reference adds its rounding error
+        let x = [Fast(0.1f64)];
+        assert_eq!(dot_spread(&x, &x).reference, Fast(0.1 * 0.1));
+    }
+
+    #[test]
+    fn conditioning() {
+        let xs = [Fast(1f64); 1000];
+        assert_eq!(sum_condition_number(&xs), Some(Fast(1.)));
+        assert_eq!(recommend_sum(&xs, Fast(1e-12)), SumMethod::Fast);
+
+        // Σ|x| = 2e8 + 1, Σx = 1
+        let xs = [Fast(1e8f64), Fast(1.), Fast(-1e8)];
+        assert_eq!(sum_condition_number(&xs), Some(Fast(2e8 + 1.)));
+        assert_eq!(recommend_sum(&xs, Fast(1e-7)), SumMethod::Fast);
+        assert_eq!(recommend_sum([xs; 20].as_flattened(), Fast(1e-6)), SumMethod::Pairwise);
+        assert_eq!(recommend_sum(&xs, Fast(1e-12)), SumMethod::Compensated);
+
+        assert_eq!(sum_condition_number(&[Fast(1f32), Fast(-1.)]), None);
+        assert_eq!(sum_compensated(&[Fast(1e16f64), Fast(1.), Fast(-1e16)]), Fast(1.));
+    }
+}
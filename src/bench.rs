@@ -0,0 +1,203 @@
+//! Helpers for microbenchmarks.
+//!
+//! The crate is `no_std`, so time is read through the [`Clock`] trait; any
+//! `FnMut() -> u64` is a clock, for example one reading `std::time::Instant` in nanoseconds.
+//! On x86 and x86_64 [`Tsc`] counts (reference) cycles.
+//!
+//! ```
+//! use fast_floats::bench::{bench, Clock};
+//! use fast_floats::{slice, Fast};
+//!
+//! let start = std::time::Instant::now();
+//! let clock = || start.elapsed().as_nanos() as u64;
+//!
+//! let data = [unsafe { Fast::new(1.) }; 1024];
+//! let m = bench(clock, 100, &data[..], |xs| slice::sum(xs));
+//! let ns_per_iter = m.per_iteration();
+//! # let _ = ns_per_iter;
+//! ```
+
+use std::hint::black_box;
+
+/// A source of timestamps, in arbitrary but consistent units
+pub trait Clock {
+    /// Return the current timestamp
+    fn now(&mut self) -> u64;
+}
+
+impl<C> Clock for C
+    where C: FnMut() -> u64,
+{
+    fn now(&mut self) -> u64 { self() }
+}
+
+/// Clock reading the x86 time stamp counter, which counts cycles at a constant rate
+/// (usually the nominal frequency of the CPU).
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+#[derive(Copy, Clone, Debug, Default)]
+pub struct Tsc;
+
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+impl Clock for Tsc {
+    #[inline]
+    fn now(&mut self) -> u64 {
+        #[cfg(target_arch = "x86")]
+        use std::arch::x86::_rdtsc;
+        #[cfg(target_arch = "x86_64")]
+        use std::arch::x86_64::_rdtsc;
+        unsafe { _rdtsc() }
+    }
+}
+
+/// The result of [`bench`]
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Measurement {
+    /// Number of timed iterations
+    pub iterations: u64,
+    /// Total elapsed time for all iterations, in clock units
+    pub elapsed: u64,
+}
+
+impl Measurement {
+    /// Return the average time per iteration, in clock units
+    pub fn per_iteration(&self) -> f64 {
+        self.elapsed as f64 / Ord::max(self.iterations, 1) as f64
+    }
+}
+
+/// Run `f(input)` `iterations` times and measure the elapsed time using `clock`.
+///
+/// `f` is called once before timing starts, as a warmup. In each iteration the input is
+/// passed through [`black_box`] so that the compiler can not hoist the work out of the
+/// loop, and the result is passed through [`black_box`] so that the work is not removed.
+pub fn bench<C, T, R>(mut clock: C, iterations: u64, input: T, mut f: impl FnMut(T) -> R)
+    -> Measurement
+    where C: Clock,
+          T: Copy,
+{
+    black_box(f(black_box(input)));
+    let start = clock.now();
+    for _ in 0..iterations {
+        black_box(f(black_box(input)));
+    }
+    let end = clock.now();
+    Measurement { iterations, elapsed: end.wrapping_sub(start) }
+}
+
+/// Compare a strict and a fast implementation of the same computation.
+///
+/// The harness measures both implementations over the same inputs and reports their timings
+/// and how far the fast results diverge from the strict results, in a [`Comparison`].
+///
+/// ```
+/// use fast_floats::bench::BenchHarness;
+/// use fast_floats::{slice, Fast};
+///
+/// let start = std::time::Instant::now();
+/// let mut harness = BenchHarness::new(|| start.elapsed().as_nanos() as u64, 10);
+///
+/// let data: Vec<f64> = (0..1000).map(|i| 1. / (i + 1) as f64).collect();
+/// let fast_data: Vec<Fast<f64>> = data.iter().map(|&x| unsafe { Fast::new(x) }).collect();
+/// let report = harness.compare(&[(&data[..], &fast_data[..])],
+///                              |(xs, _)| xs.iter().sum::<f64>(),
+///                              |(_, xs)| slice::sum(xs).get());
+/// assert!(report.max_rel_diff < 1e-12);
+/// # let _ = report.speedup();
+/// ```
+pub struct BenchHarness<C> {
+    clock: C,
+    iterations: u64,
+}
+
+/// The result of [`BenchHarness::compare`]
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Comparison {
+    /// Timing of the strict implementation; each iteration runs all inputs
+    pub strict: Measurement,
+    /// Timing of the fast implementation; each iteration runs all inputs
+    pub fast: Measurement,
+    /// Largest absolute difference between a strict and fast result
+    pub max_abs_diff: f64,
+    /// Mean absolute difference between the strict and fast results
+    pub mean_abs_diff: f64,
+    /// Largest difference relative to the magnitude of the strict result
+    ///
+    /// Inputs where the strict result is zero are skipped.
+    pub max_rel_diff: f64,
+}
+
+impl Comparison {
+    /// Return how many times faster the fast implementation is than the strict one
+    pub fn speedup(&self) -> f64 {
+        self.strict.per_iteration() / self.fast.per_iteration()
+    }
+}
+
+impl<C: Clock> BenchHarness<C> {
+    /// Create a new harness that times `iterations` runs over the inputs, using `clock`
+    pub fn new(clock: C, iterations: u64) -> Self {
+        BenchHarness { clock, iterations }
+    }
+
+    /// Measure `strict` and `fast` over `inputs`, and compare their results.
+    pub fn compare<T, R>(&mut self, inputs: &[T],
+                         mut strict: impl FnMut(T) -> R,
+                         mut fast: impl FnMut(T) -> R) -> Comparison
+        where T: Copy,
+              R: Into<f64>,
+    {
+        let mut max_abs_diff = 0.;
+        let mut sum_abs_diff = 0.;
+        let mut max_rel_diff = 0.;
+        for &input in inputs {
+            let s = strict(input).into();
+            let f = fast(input).into();
+            let diff = (s - f).abs();
+            max_abs_diff = f64::max(max_abs_diff, diff);
+            sum_abs_diff += diff;
+            if s != 0. {
+                max_rel_diff = f64::max(max_rel_diff, diff / s.abs());
+            }
+        }
+        let mean_abs_diff = if inputs.is_empty() { 0. } else { sum_abs_diff / inputs.len() as f64 };
+
+        let strict = self.run(inputs, &mut strict);
+        let fast = self.run(inputs, &mut fast);
+        Comparison { strict, fast, max_abs_diff, mean_abs_diff, max_rel_diff }
+    }
+
+    fn run<T: Copy, R>(&mut self, inputs: &[T], f: &mut impl FnMut(T) -> R) -> Measurement {
+        let clock = &mut self.clock;
+        bench(|| clock.now(), self.iterations, inputs, |inputs| {
+            for &input in inputs {
+                black_box(f(black_box(input)));
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn counts() {
+        let mut ticks = 0;
+        let mut calls = 0;
+        let m = bench(|| { ticks += 10; ticks }, 5, 2., |x: f64| { calls += 1; x * x });
+        assert_eq!(calls, 6);
+        assert_eq!(m, Measurement { iterations: 5, elapsed: 10 });
+        assert_eq!(m.per_iteration(), 2.);
+    }
+
+    #[test]
+    fn compare() {
+        let mut ticks = 0;
+        let mut harness = BenchHarness::new(|| { ticks += 1; ticks }, 3);
+        let report = harness.compare(&[1., 2., 4.], |x: f64| x, |x| x + 1.);
+        assert_eq!(report.max_abs_diff, 1.);
+        assert_eq!(report.mean_abs_diff, 1.);
+        assert_eq!(report.max_rel_diff, 1.);
+        assert_eq!(report.speedup(), 1.);
+    }
+}
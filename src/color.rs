@@ -0,0 +1,93 @@
+//! Conversions between sRGB-encoded and linear-light color values.
+//!
+//! The values are color components in `0..=1`. The sRGB transfer function is linear near
+//! zero and a power function with exponent 2.4 above; the [`Fast`] methods compute the
+//! power with [`Fast::powf`], and the slice routines with [`approx::powf`], evaluating both
+//! pieces and selecting between them, so that they vectorize.
+//!
+//! ```
+//! use fast_floats::{color, Fast};
+//!
+//! let f = |x| unsafe { Fast::new(x) };
+//! let srgb = [f(0f64), f(0.5), f(1.)];
+//! let mut linear = [f(0.); 3];
+//! color::srgb_to_linear(&srgb, &mut linear);
+//! assert!((linear[1].get() - 0.21404).abs() < 1e-5);
+//! assert!((linear[1] - f(0.5).srgb_to_linear()).abs() < f(1e-6));
+//! ```
+
+use crate::approx;
+use crate::{Fast, FastFloat};
+
+// the ends of the linear pieces, in sRGB and in linear values
+const SRGB_KNEE: f64 = 0.04045;
+const LINEAR_KNEE: f64 = 0.0031308;
+
+impl<F: FastFloat> Fast<F> {
+    /// Convert an sRGB-encoded color component to linear light
+    #[inline]
+    pub fn srgb_to_linear(self) -> Self {
+        if self <= Fast(F::from_f64(SRGB_KNEE)) {
+            self * F::from_f64(1. / 12.92)
+        } else {
+            ((self + F::from_f64(0.055)) * F::from_f64(1. / 1.055)).powf(Fast(F::from_f64(2.4)))
+        }
+    }
+
+    /// Convert a linear-light color component to sRGB encoding
+    #[inline]
+    pub fn linear_to_srgb(self) -> Self {
+        if self <= Fast(F::from_f64(LINEAR_KNEE)) {
+            self * F::from_f64(12.92)
+        } else {
+            self.powf(Fast(F::from_f64(1. / 2.4))) * F::from_f64(1.055) - F::from_f64(0.055)
+        }
+    }
+}
+
+/// Convert sRGB-encoded color components to linear light, `out[i] = linear(xs[i])`.
+///
+/// ***Panics*** if the slices are not of equal length.
+pub fn srgb_to_linear<F: FastFloat>(xs: &[Fast<F>], out: &mut [Fast<F>]) {
+    assert_eq!(xs.len(), out.len(), "srgb_to_linear: slices must be of equal length");
+    for (o, &x) in out.iter_mut().zip(xs) {
+        let linear = x * F::from_f64(1. / 12.92);
+        let base = (x + F::from_f64(0.055)) * F::from_f64(1. / 1.055);
+        let power = approx::powf(base, Fast(F::from_f64(2.4)));
+        *o = Fast::select(x <= Fast(F::from_f64(SRGB_KNEE)), linear, power);
+    }
+}
+
+/// Convert linear-light color components to sRGB encoding, `out[i] = srgb(xs[i])`.
+///
+/// ***Panics*** if the slices are not of equal length.
+pub fn linear_to_srgb<F: FastFloat>(xs: &[Fast<F>], out: &mut [Fast<F>]) {
+    assert_eq!(xs.len(), out.len(), "linear_to_srgb: slices must be of equal length");
+    for (o, &x) in out.iter_mut().zip(xs) {
+        let linear = x * F::from_f64(12.92);
+        let power = approx::powf(x, Fast(F::from_f64(1. / 2.4))) * F::from_f64(1.055)
+            - F::from_f64(0.055);
+        *o = Fast::select(x <= Fast(F::from_f64(LINEAR_KNEE)), linear, power);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn conversions() {
+        let xs: [Fast<f64>; 11] = std::array::from_fn(|i| Fast(i as f64 / 10.));
+        let (mut linear, mut srgb) = ([Fast(0.); 11], [Fast(0.); 11]);
+        srgb_to_linear(&xs, &mut linear);
+        linear_to_srgb(&linear, &mut srgb);
+        for i in 0..11 {
+            assert!((linear[i] - xs[i].srgb_to_linear()).abs() < Fast(1e-6), "{}", i);
+            assert!((srgb[i] - xs[i]).abs() < Fast(1e-6), "{}", i);
+            assert!((linear[i].linear_to_srgb() - xs[i]).abs() < Fast(1e-6), "{}", i);
+        }
+        assert_eq!(linear[0], Fast(0.));
+        assert!((Fast(0.5f64).linear_to_srgb().get() - 0.735357).abs() < 1e-6);
+        assert_eq!(Fast(0.002f64).linear_to_srgb(), Fast(0.002 * 12.92));
+    }
+}
@@ -0,0 +1,137 @@
+//! Complex numbers of fast values, and FFT butterflies.
+
+use std::ops::{Add, Mul, Neg, Sub};
+
+use crate::{Fast, FastFloat};
+
+/// A complex number with fast real and imaginary parts
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Complex<F> {
+    /// Real part
+    pub re: Fast<F>,
+    /// Imaginary part
+    pub im: Fast<F>,
+}
+
+impl<F> Complex<F> {
+    /// Create a new complex number
+    pub fn new(re: Fast<F>, im: Fast<F>) -> Self { Complex { re, im } }
+}
+
+impl<F: FastFloat> Complex<F> {
+    /// Return the complex conjugate
+    #[inline]
+    pub fn conj(self) -> Self { Complex::new(self.re, -self.im) }
+
+    /// Return the squared magnitude, `re² + im²`
+    #[inline]
+    pub fn norm_sqr(self) -> Fast<F> { self.re * self.re + self.im * self.im }
+
+    /// Return `self * conj(rhs)`, without computing the conjugate separately
+    #[inline]
+    pub fn conj_mul(self, rhs: Self) -> Self {
+        Complex::new(self.re * rhs.re + self.im * rhs.im,
+                     self.im * rhs.re - self.re * rhs.im)
+    }
+
+    /// Return `self * i`
+    #[inline]
+    pub fn mul_i(self) -> Self { Complex::new(-self.im, self.re) }
+
+    /// Return `self * -i`
+    #[inline]
+    pub fn mul_neg_i(self) -> Self { Complex::new(self.im, -self.re) }
+}
+
+impl<F: FastFloat> Add for Complex<F> {
+    type Output = Self;
+    #[inline]
+    fn add(self, rhs: Self) -> Self { Complex::new(self.re + rhs.re, self.im + rhs.im) }
+}
+
+impl<F: FastFloat> Sub for Complex<F> {
+    type Output = Self;
+    #[inline]
+    fn sub(self, rhs: Self) -> Self { Complex::new(self.re - rhs.re, self.im - rhs.im) }
+}
+
+impl<F: FastFloat> Mul for Complex<F> {
+    type Output = Self;
+    /// Complex multiplication, written so that it can contract to two FMA per part
+    #[inline]
+    fn mul(self, rhs: Self) -> Self {
+        Complex::new(self.re * rhs.re - self.im * rhs.im,
+                     self.re * rhs.im + self.im * rhs.re)
+    }
+}
+
+impl<F: FastFloat> Mul<Fast<F>> for Complex<F> {
+    type Output = Self;
+    #[inline]
+    fn mul(self, rhs: Fast<F>) -> Self { Complex::new(self.re * rhs, self.im * rhs) }
+}
+
+impl<F: FastFloat> Neg for Complex<F> {
+    type Output = Self;
+    #[inline]
+    fn neg(self) -> Self { Complex::new(-self.re, -self.im) }
+}
+
+/// Radix-2 decimation-in-time butterfly with twiddle factor `w`.
+///
+/// Return `(a + w b, a - w b)`.
+#[inline]
+pub fn butterfly2<F: FastFloat>(a: Complex<F>, b: Complex<F>, w: Complex<F>) -> (Complex<F>, Complex<F>) {
+    let t = w * b;
+    (a + t, a - t)
+}
+
+/// Radix-4 decimation-in-time butterfly for the forward transform (exponent sign -1).
+///
+/// The inputs `x[1]`, `x[2]`, `x[3]` are multiplied by the twiddle factors `w[0]`, `w[1]`,
+/// `w[2]` and then combined by a 4-point DFT.
+#[inline]
+pub fn butterfly4<F: FastFloat>(x: [Complex<F>; 4], w: [Complex<F>; 3]) -> [Complex<F>; 4] {
+    let (a, b, c, d) = (x[0], w[0] * x[1], w[1] * x[2], w[2] * x[3]);
+    let (s0, d0) = (a + c, a - c);
+    let (s1, d1) = (b + d, (b - d).mul_neg_i());
+    [s0 + s1, d0 + d1, s0 - s1, d0 - d1]
+}
+
+/// Radix-4 decimation-in-time butterfly for the inverse transform (exponent sign +1).
+///
+/// Like [`butterfly4`] but the 4-point DFT rotates by `+i`; the twiddle factors should be
+/// those of the inverse transform. No scaling is applied.
+#[inline]
+pub fn butterfly4_inverse<F: FastFloat>(x: [Complex<F>; 4], w: [Complex<F>; 3]) -> [Complex<F>; 4] {
+    let (a, b, c, d) = (x[0], w[0] * x[1], w[1] * x[2], w[2] * x[3]);
+    let (s0, d0) = (a + c, a - c);
+    let (s1, d1) = (b + d, (b - d).mul_i());
+    [s0 + s1, d0 + d1, s0 - s1, d0 - d1]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn c(re: f64, im: f64) -> Complex<f64> { Complex::new(Fast(re), Fast(im)) }
+
+    #[test]
+    fn arithmetic() {
+        assert_eq!(c(1., 2.) * c(3., 4.), c(-5., 10.));
+        assert_eq!(c(1., 2.).conj_mul(c(3., 4.)), c(1., 2.) * c(3., -4.));
+        assert_eq!(c(3., 4.).norm_sqr(), Fast(25.));
+    }
+
+    #[test]
+    fn butterflies() {
+        let one = c(1., 0.);
+        assert_eq!(butterfly2(c(1., 0.), c(2., 0.), one), (c(3., 0.), c(-1., 0.)));
+        // DFT of [1, 2, 3, 4]
+        let x = [c(1., 0.), c(2., 0.), c(3., 0.), c(4., 0.)];
+        let y = butterfly4(x, [one; 3]);
+        assert_eq!(y, [c(10., 0.), c(-2., 2.), c(-2., 0.), c(-2., -2.)]);
+        let z = butterfly4_inverse(y, [one; 3]);
+        assert_eq!(z, [c(4., 0.), c(8., 0.), c(12., 0.), c(16., 0.)]);
+    }
+}
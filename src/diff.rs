@@ -0,0 +1,75 @@
+//! Numerical differentiation with finite differences.
+//!
+//! ```
+//! use fast_floats::{diff, Fast};
+//!
+//! let f = |x| unsafe { Fast::new(x) };
+//! // the gradient of x² + 3xy is (2x + 3y, 3x)
+//! let mut x = [f(1f64), f(2.)];
+//! let mut grad = [f(0.); 2];
+//! diff::gradient_fd(|x| x[0] * x[0] + f(3.) * x[0] * x[1], &mut x, f(1e-5), &mut grad);
+//! assert!((grad[0].get() - 8.).abs() < 1e-8 && (grad[1].get() - 3.).abs() < 1e-8);
+//! ```
+
+use crate::{Fast, FastFloat};
+
+/// Compute the gradient of `f` at `x` with central differences, writing it to `out`.
+///
+/// Coordinate `i` is perturbed with the step `eps * max(|x[i]|, 1)`, rounded so that
+/// `x[i] ± h` is exact; the derivative is `(f(x + h) - f(x - h)) / 2h`. A relative step
+/// near the cube root of the machine epsilon, about `1e-5` for `f64` and `5e-3` for `f32`,
+/// balances the truncation and rounding errors for smooth functions. `eps` must be at least
+/// the machine epsilon (`f64::EPSILON` or `f32::EPSILON`), so that the rounded step is not
+/// zero.
+///
+/// `x` is perturbed in place, one coordinate at a time, and is restored before returning.
+/// `f` is called `2 * x.len()` times.
+///
+/// ***Panics*** if the slices are not of equal length, or if the rounded step is zero.
+pub fn gradient_fd<F: FastFloat>(mut f: impl FnMut(&[Fast<F>]) -> Fast<F>, x: &mut [Fast<F>],
+                                 eps: Fast<F>, out: &mut [Fast<F>])
+{
+    assert_eq!(x.len(), out.len(), "gradient_fd: slices must be of equal length");
+    for (i, out) in out.iter_mut().enumerate() {
+        let xi = x[i];
+        let h = F::mul_strict(eps.0, xi.0.abs().max(F::ONE));
+        // the step that is actually taken, so that the difference quotient uses it
+        let h = F::sub_strict(F::add_strict(xi.0, h), xi.0);
+        assert!(h != F::ZERO, "gradient_fd: eps is too small, the step for x[{}] is zero", i);
+        x[i] = Fast(F::add_strict(xi.0, h));
+        let up = f(x);
+        x[i] = Fast(F::sub_strict(xi.0, h));
+        let down = f(x);
+        x[i] = xi;
+        *out = (up - down) / (Fast(h) + Fast(h));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn gradient() {
+        let f = |x: &[Fast<f64>]| x[0] * x[1] * x[2] + x[0] * x[0] * x[0];
+        let x0 = [Fast(1e3), Fast(-0.5), Fast(0.)];
+        let mut x = x0;
+        let mut grad = [Fast(0.); 3];
+        gradient_fd(f, &mut x, Fast(1e-5), &mut grad);
+        assert_eq!(x, x0);
+        let expected = [3e6, 0., -500.];
+        for (g, e) in grad.iter().zip(expected) {
+            assert!((g.get() - e).abs() <= 1e-6 * e.abs().max(1.), "{:?}", grad);
+        }
+        // the smallest usable eps still gives a nonzero step
+        gradient_fd(f, &mut x, Fast(f64::EPSILON), &mut grad);
+        assert!(grad.iter().all(|g| g.get().is_finite()));
+    }
+
+    #[test]
+    #[should_panic(expected = "eps is too small")]
+    fn zero_step() {
+        let mut grad = [Fast(0f64)];
+        gradient_fd(|x| x[0], &mut [Fast(1.)], Fast(1e-20), &mut grad);
+    }
+}
@@ -0,0 +1,623 @@
+//! Signal processing routines.
+
+use std::f64::consts::{LN_10, TAU};
+use std::intrinsics::floorf64;
+
+use crate::approx;
+use crate::complex::Complex;
+use crate::slice::{dot, peak, scale, sum_of_squares};
+use crate::{Fast, FastFloat};
+
+/// Compute the cross-correlation of `a` and `b` for the lags `0..out.len()`:
+/// `out[k] = Σ a[k + i] * b[i]`, the dot product of `b` with the window of `a` starting at
+/// `k`.
+///
+/// Each lag is computed with the [`dot`] kernel.
+///
+/// ***Panics*** if the windows do not fit in `a`, that is if `b.len() + out.len() - 1` is
+/// larger than `a.len()`.
+///
+/// ```
+/// use fast_floats::dsp::xcorr;
+/// use fast_floats::Fast;
+///
+/// let f = |x| unsafe { Fast::new(x) };
+/// let signal = [0., 1., 2., 1., 0.].map(f);
+/// let mut out = [f(0.); 3];
+/// xcorr(&signal, &[f(1.), f(2.), f(1.)], &mut out);
+/// assert_eq!(out, [4., 6., 4.].map(f));
+/// ```
+pub fn xcorr<F: FastFloat>(a: &[Fast<F>], b: &[Fast<F>], out: &mut [Fast<F>]) {
+    check_windows(a, b, out, "xcorr");
+    for (k, o) in out.iter_mut().enumerate() {
+        *o = dot(&a[k..k + b.len()], b);
+    }
+}
+
+/// Compute the normalized cross-correlation of `a` and `b` for the lags `0..out.len()`.
+///
+/// This is [`xcorr`] divided by the norms of `b` and of each window of `a`, so that the
+/// result is in `-1..=1` and is one where the window is a positive multiple of `b`. A lag
+/// where either norm is zero is zero.
+///
+/// ***Panics*** like [`xcorr`].
+pub fn xcorr_normalized<F: FastFloat>(a: &[Fast<F>], b: &[Fast<F>], out: &mut [Fast<F>]) {
+    check_windows(a, b, out, "xcorr_normalized");
+    let zero = Fast(F::ZERO);
+    let b_energy = sum_of_squares(b);
+    for (k, o) in out.iter_mut().enumerate() {
+        let window = &a[k..k + b.len()];
+        let energy = sum_of_squares(window) * b_energy;
+        *o = if energy == zero { zero } else { dot(window, b) / Fast(energy.0.sqrt_strict()) };
+    }
+}
+
+/// Compute the autocorrelation of `signal` for the lags `0..=max_lag`:
+/// `out[k] = Σ signal[i] * signal[i + k]`.
+///
+/// Each lag is computed with the [`dot`] kernel. Lags of the length of the signal or more
+/// are zero.
+///
+/// ***Panics*** if `out.len()` is not `max_lag + 1`.
+pub fn autocorr<F: FastFloat>(signal: &[Fast<F>], max_lag: usize, out: &mut [Fast<F>]) {
+    assert_eq!(out.len(), max_lag + 1, "autocorr: out must have max_lag + 1 elements");
+    let n = signal.len();
+    for (k, o) in out.iter_mut().enumerate() {
+        *o = if k < n { dot(&signal[..n - k], &signal[k..]) } else { Fast(F::ZERO) };
+    }
+}
+
+/// Compute the autocorrelation of `signal` for the lags `0..=max_lag`, normalized by the
+/// energy of the signal so that lag zero is one.
+///
+/// If the signal is all zeros, the result is all zeros.
+///
+/// ***Panics*** if `out.len()` is not `max_lag + 1`.
+pub fn autocorr_normalized<F: FastFloat>(signal: &[Fast<F>], max_lag: usize, out: &mut [Fast<F>]) {
+    autocorr(signal, max_lag, out);
+    let zero = Fast(F::ZERO);
+    let energy = sum_of_squares(signal);
+    if energy == zero {
+        out.fill(zero);
+    } else {
+        // 1 / energy overflows for a subnormal energy, the quotients do not
+        for o in out {
+            *o /= energy;
+        }
+    }
+}
+
+/// The output size of a convolution, as in NumPy and Matlab.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum ConvMode {
+    /// All the points where the signals overlap, length `n + m - 1`
+    Full,
+    /// The center part of `Full`, of the length of the longer signal, `max(n, m)`
+    Same,
+    /// Only the points where the signals overlap completely, length `max(n, m) - min(n, m) + 1`
+    Valid,
+}
+
+impl ConvMode {
+    /// Return the output length for signals of lengths `n` and `m`, or zero if either is empty
+    pub fn output_len(self, n: usize, m: usize) -> usize {
+        let (long, short) = (n.max(m), n.min(m));
+        if short == 0 {
+            return 0;
+        }
+        match self {
+            ConvMode::Full => long + short - 1,
+            ConvMode::Same => long,
+            ConvMode::Valid => long - short + 1,
+        }
+    }
+
+    /// Return the index in the full convolution of the first output
+    fn start(self, short: usize) -> usize {
+        match self {
+            ConvMode::Full => 0,
+            ConvMode::Same => (short - 1) / 2,
+            ConvMode::Valid => short - 1,
+        }
+    }
+}
+
+/// Compute the convolution of `a` and `b` into `out`: the part of the full convolution
+/// `c[k] = Σ a[j] * b[k - j]` that `mode` selects.
+///
+/// The result matches `numpy.convolve(a, b, mode)` (which also puts the longer signal
+/// first). The output length is [`mode.output_len(a.len(), b.len())`](ConvMode::output_len).
+///
+/// ***Panics*** if `out` does not have the output length.
+///
+/// ```
+/// use fast_floats::dsp::{convolve, ConvMode};
+/// use fast_floats::Fast;
+///
+/// let f = |x| unsafe { Fast::new(x) };
+/// let mut out = [f(0.); 3];
+/// convolve(&[1., 2., 3.].map(f), &[0., 1., 0.5].map(f), ConvMode::Same, &mut out);
+/// assert_eq!(out, [1., 2.5, 4.].map(f));
+/// ```
+pub fn convolve<F: FastFloat>(a: &[Fast<F>], b: &[Fast<F>], mode: ConvMode, out: &mut [Fast<F>]) {
+    assert_eq!(out.len(), mode.output_len(a.len(), b.len()),
+               "convolve: out must have the output length of the mode");
+    let (a, b) = if a.len() >= b.len() { (a, b) } else { (b, a) };
+    if b.is_empty() {
+        return;
+    }
+    let start = mode.start(b.len());
+    for (i, o) in out.iter_mut().enumerate() {
+        let k = start + i;
+        // j in lo..=hi with 0 <= j < a.len() and 0 <= k - j < b.len()
+        let lo = (k + 1).saturating_sub(b.len());
+        let hi = k.min(a.len() - 1);
+        let bs = &b[k - hi..=k - lo];
+        *o = a[lo..=hi].iter().zip(bs.iter().rev())
+                       .fold(Fast(F::ZERO), |acc, (&x, &y)| x * y + acc);
+    }
+}
+
+/// Convert a level in decibels to a linear amplitude gain, `10^(db / 20)`.
+///
+/// Uses [`approx::exp`], so the result is always finite and positive.
+#[inline]
+pub fn db_to_gain<F: FastFloat>(db: Fast<F>) -> Fast<F> {
+    approx::exp(db * F::from_f64(LN_10 / 20.))
+}
+
+/// Convert a linear amplitude gain to a level in decibels, `20 log10(gain)`.
+///
+/// Uses [`approx::ln`], so a gain of zero or below is treated as the smallest positive
+/// normal value (about -758 dB for `f32`), and the result is always finite.
+#[inline]
+pub fn gain_to_db<F: FastFloat>(gain: Fast<F>) -> Fast<F> {
+    approx::ln(gain) * F::from_f64(20. / LN_10)
+}
+
+/// Multiply each sample in `xs` by `gain`.
+pub fn apply_gain<F: FastFloat>(xs: &mut [Fast<F>], gain: Fast<F>) {
+    scale(xs, gain)
+}
+
+/// Multiply each sample in `xs` by the gain of `db` decibels, see [`db_to_gain`].
+pub fn apply_gain_db<F: FastFloat>(xs: &mut [Fast<F>], db: Fast<F>) {
+    scale(xs, db_to_gain(db))
+}
+
+/// Scale `xs` so that its largest magnitude is `target`, and return the gain that was
+/// applied.
+///
+/// The peak is found with [`slice::min_max`](crate::slice::min_max) in one pass, and the
+/// samples are scaled in a second. If all samples are zero, or the peak is so small (such as
+/// a subnormal decay tail) that the gain would overflow, `xs` is not changed and the gain is
+/// one. See [`limit_peak`] to only scale down.
+pub fn normalize_peak<F: FastFloat>(xs: &mut [Fast<F>], target: Fast<F>) -> Fast<F> {
+    let peak = peak(xs);
+    if peak == Fast(F::ZERO) {
+        return Fast(F::ONE);
+    }
+    let gain = F::div_strict(target.0, peak.0);
+    if !gain.is_finite() {
+        return Fast(F::ONE);
+    }
+    scale(xs, Fast(gain));
+    Fast(gain)
+}
+
+/// Scale `xs` down so that its largest magnitude is `target`, if it is above `target`, and
+/// return the gain that was applied.
+///
+/// If the peak is already at or below `target`, `xs` is not changed and the gain is one.
+pub fn limit_peak<F: FastFloat>(xs: &mut [Fast<F>], target: Fast<F>) -> Fast<F> {
+    let peak = peak(xs);
+    if peak <= target {
+        return Fast(F::ONE);
+    }
+    let gain = target / peak;
+    scale(xs, gain);
+    gain
+}
+
+/// A linear interpolation resampler with a fractional ratio.
+///
+/// Output sample `i` is interpolated at input position `i * step`, where `step` is the
+/// number of input samples per output sample. The position is kept as an integer index and
+/// a fractional phase, so it does not lose precision for long buffers.
+///
+/// ```
+/// use fast_floats::dsp::Resampler;
+/// use fast_floats::Fast;
+///
+/// let f = |x| unsafe { Fast::new(x) };
+/// // upsample by 2
+/// let resampler = Resampler::new(f(2.));
+/// let input = [0., 1., 0.].map(f);
+/// let mut output = [f(0.); 5];
+/// assert_eq!(resampler.output_len(input.len()), output.len());
+/// resampler.resample(&input, &mut output);
+/// assert_eq!(output, [0., 0.5, 1., 0.5, 0.].map(f));
+/// ```
+#[derive(Copy, Clone, Debug)]
+pub struct Resampler<F> {
+    step_int: usize,
+    step_frac: Fast<F>,
+    step: f64,
+}
+
+impl<F: FastFloat> Resampler<F> {
+    /// Create a resampler from the ratio of the output rate to the input rate.
+    ///
+    /// ***Panics*** if `ratio` is not positive.
+    pub fn new(ratio: Fast<F>) -> Self {
+        assert!(ratio > Fast(F::ZERO), "Resampler: ratio must be positive, got {:?}", ratio);
+        // the step is split in f64, so that its fractional part is rounded only once; the
+        // integer part saturates for steps beyond usize::MAX, and the step itself is clamped
+        // for subnormal ratios, where it would be infinite
+        let step = (1. / ratio.0.to_f64()).min(f64::MAX);
+        let step_int = step as usize;
+        Resampler { step_int, step_frac: Fast(F::from_f64(step - floorf64(step))), step }
+    }
+
+    /// Return the number of input samples per output sample
+    pub fn step(&self) -> f64 { self.step }
+
+    /// Return the number of output samples whose positions are inside an input of
+    /// length `input_len`.
+    pub fn output_len(&self, input_len: usize) -> usize {
+        if input_len == 0 {
+            return 0;
+        }
+        (((input_len - 1) as f64 / self.step) as usize).saturating_add(1)
+    }
+
+    /// Fill `output` with samples interpolated from `input`.
+    ///
+    /// Positions past the last input sample repeat the last input sample.
+    ///
+    /// ***Panics*** if `input` is empty and `output` is not.
+    pub fn resample(&self, input: &[Fast<F>], output: &mut [Fast<F>]) {
+        if output.is_empty() {
+            return;
+        }
+        assert!(!input.is_empty(), "Resampler::resample: input must not be empty");
+        let last = input.len() - 1;
+        let (mut index, mut phase) = (0, Fast(F::ZERO));
+        for o in output {
+            let x0 = input[index.min(last)];
+            let x1 = input[(index + 1).min(last)];
+            *o = phase * (x1 - x0) + x0;
+            phase += self.step_frac;
+            if phase >= Fast(F::ONE) {
+                phase -= F::ONE;
+                index += 1;
+            }
+            // once past the end, stay at the last sample
+            index = index.saturating_add(self.step_int).min(last);
+        }
+    }
+}
+
+/// The Goertzel algorithm: one bin of the discrete Fourier transform, computed over
+/// streamed samples.
+///
+/// After `n` samples, [`bin`](Goertzel::bin) is the DFT of the samples at the chosen
+/// frequency (which need not be an integer bin of `n`), up to a phase factor that only
+/// depends on `n` and the frequency. Each sample costs one multiply and two adds.
+///
+/// ```
+/// use fast_floats::dsp::Goertzel;
+/// use fast_floats::Fast;
+///
+/// let f = |x| unsafe { Fast::new(x) };
+/// let mut tone = Goertzel::new(f(1000.), f(8000.));
+/// for i in 0..80 {
+///     tone.push(f((i as f64 * std::f64::consts::TAU / 8.).sin()));
+/// }
+/// assert!((tone.magnitude().get() - 40.).abs() < 1e-9);
+/// ```
+#[derive(Copy, Clone, Debug)]
+pub struct Goertzel<F> {
+    coeff: Fast<F>,
+    cos: Fast<F>,
+    sin: Fast<F>,
+    s1: Fast<F>,
+    s2: Fast<F>,
+}
+
+impl<F: FastFloat> Goertzel<F> {
+    /// Create a detector for `frequency` in a signal sampled at `sample_rate`
+    pub fn new(frequency: Fast<F>, sample_rate: Fast<F>) -> Self {
+        let omega = frequency / sample_rate * F::from_f64(TAU);
+        let (sin, cos) = omega.0.sin_cos_strict();
+        let (sin, cos) = (Fast(sin), Fast(cos));
+        let zero = Fast(F::ZERO);
+        Goertzel { coeff: cos + cos, cos, sin, s1: zero, s2: zero }
+    }
+
+    /// Add the next sample
+    #[inline]
+    pub fn push(&mut self, x: Fast<F>) {
+        let s0 = self.coeff * self.s1 - self.s2 + x;
+        self.s2 = self.s1;
+        self.s1 = s0;
+    }
+
+    /// Forget all samples
+    pub fn reset(&mut self) {
+        self.s1 = Fast(F::ZERO);
+        self.s2 = Fast(F::ZERO);
+    }
+
+    /// Return the squared magnitude of the bin
+    pub fn power(&self) -> Fast<F> {
+        self.s1 * self.s1 + self.s2 * self.s2 - self.coeff * self.s1 * self.s2
+    }
+
+    /// Return the magnitude of the bin
+    pub fn magnitude(&self) -> Fast<F> { Fast(self.power().max(Fast(F::ZERO)).0.sqrt_strict()) }
+
+    /// Return the bin as a complex number
+    pub fn bin(&self) -> Complex<F> {
+        Complex::new(self.s1 - self.s2 * self.cos, self.s2 * self.sin)
+    }
+
+    /// Return the phase of the bin in radians, using [`approx::atan2`]
+    pub fn phase(&self) -> Fast<F> {
+        let bin = self.bin();
+        approx::atan2(bin.im, bin.re)
+    }
+}
+
+impl<F: FastFloat> Extend<Fast<F>> for Goertzel<F> {
+    fn extend<I: IntoIterator<Item = Fast<F>>>(&mut self, iter: I) {
+        for x in iter {
+            self.push(x);
+        }
+    }
+}
+
+/// An envelope follower: a one pole smoother of the rectified signal, with separate
+/// coefficients for rising (attack) and falling (release) levels.
+///
+/// Each sample moves the envelope by `coeff * (|x| - envelope)`, where `coeff` is the attack
+/// coefficient when `|x|` is above the envelope and the release coefficient otherwise.
+///
+/// ```
+/// use fast_floats::dsp::EnvelopeFollower;
+/// use fast_floats::Fast;
+///
+/// let f = |x| unsafe { Fast::new(x) };
+/// let mut env = EnvelopeFollower::new(f(1.), f(0.5));
+/// assert_eq!(env.push(f(-4.)), f(4.));
+/// assert_eq!(env.push(f(0.)), f(2.));
+/// assert_eq!(env.push(f(1.)), f(1.5));
+/// ```
+#[derive(Copy, Clone, Debug)]
+pub struct EnvelopeFollower<F> {
+    attack: Fast<F>,
+    release: Fast<F>,
+    envelope: Fast<F>,
+}
+
+impl<F: FastFloat> EnvelopeFollower<F> {
+    /// Create an envelope follower from the attack and release coefficients.
+    ///
+    /// ***Panics*** if a coefficient is not in `0 < coeff <= 1`.
+    pub fn new(attack: Fast<F>, release: Fast<F>) -> Self {
+        for coeff in [attack, release] {
+            assert!(coeff > Fast(F::ZERO) && coeff <= Fast(F::ONE),
+                    "EnvelopeFollower: coefficients must be in the range 0 < coeff <= 1, got {:?}",
+                    coeff);
+        }
+        EnvelopeFollower { attack, release, envelope: Fast(F::ZERO) }
+    }
+
+    /// Create an envelope follower from attack and release times in seconds, for a signal
+    /// sampled at `sample_rate`.
+    ///
+    /// The time is the time constant: after a step, the envelope moves by `1 - 1/e` of the
+    /// step in that time. A time of zero follows the signal immediately.
+    ///
+    /// ***Panics*** if a time is negative.
+    pub fn from_times(attack: Fast<F>, release: Fast<F>, sample_rate: Fast<F>) -> Self {
+        let coeff = |time: Fast<F>| {
+            assert!(time >= Fast(F::ZERO), "EnvelopeFollower: times must not be negative, got {:?}",
+                    time);
+            let samples = time * sample_rate;
+            if samples <= Fast(F::MIN_POSITIVE) {
+                Fast(F::ONE)
+            } else {
+                -approx::exp(-(Fast(F::ONE) / samples)) + F::ONE
+            }
+        };
+        Self::new(coeff(attack), coeff(release))
+    }
+
+    /// Update the envelope with the sample `x` and return the new envelope
+    #[inline]
+    pub fn push(&mut self, x: Fast<F>) -> Fast<F> {
+        let level = x.abs();
+        let coeff = if level > self.envelope { self.attack } else { self.release };
+        self.envelope = coeff * (level - self.envelope) + self.envelope;
+        self.envelope
+    }
+
+    /// Update the envelope with the samples of `input` and write the envelope after each
+    /// sample to `out`.
+    ///
+    /// ***Panics*** if the slices are not of equal length.
+    pub fn process(&mut self, input: &[Fast<F>], out: &mut [Fast<F>]) {
+        assert_eq!(input.len(), out.len(),
+                   "EnvelopeFollower::process: slices must be of equal length");
+        for (o, &x) in out.iter_mut().zip(input) {
+            *o = self.push(x);
+        }
+    }
+
+    /// Return the current envelope
+    pub fn value(&self) -> Fast<F> { self.envelope }
+
+    /// Reset the envelope to zero
+    pub fn reset(&mut self) {
+        self.envelope = Fast(F::ZERO);
+    }
+}
+
+impl<F: FastFloat> Extend<Fast<F>> for EnvelopeFollower<F> {
+    fn extend<I: IntoIterator<Item = Fast<F>>>(&mut self, iter: I) {
+        for x in iter {
+            self.push(x);
+        }
+    }
+}
+
+fn check_windows<F>(a: &[F], b: &[F], out: &[F], name: &str) {
+    assert!(out.is_empty() || b.len() + out.len() - 1 <= a.len(),
+            "{}: {} lags of a window of length {} do not fit in a signal of length {}",
+            name, out.len(), b.len(), a.len());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn correlation() {
+        let a = [Fast(1.), Fast(0.), Fast(-2.), Fast(0.), Fast(4.)];
+        let mut out = [Fast(0.); 3];
+        xcorr(&a, &[Fast(1.), Fast(0.), Fast(-2.)], &mut out);
+        assert_eq!(out, [Fast(5.), Fast(0.), Fast(-10.)]);
+        xcorr_normalized(&a, &[Fast(-1.), Fast(0.), Fast(2.)], &mut out);
+        assert_eq!(out[0], Fast(-1.));
+        assert_eq!(out[1], Fast(0.));
+        xcorr_normalized(&[Fast(0.); 3], &[Fast(1.)], &mut out);
+        assert_eq!(out, [Fast(0.); 3]);
+    }
+
+    #[test]
+    fn autocorrelation() {
+        let x = [Fast(1.), Fast(2.), Fast(3.)];
+        let mut out = [Fast(0.); 4];
+        autocorr(&x, 3, &mut out);
+        assert_eq!(out, [14., 8., 3., 0.].map(Fast));
+        autocorr_normalized(&x, 1, &mut out[..2]);
+        assert_eq!(out[0], Fast(1.));
+        assert_eq!(out[1], Fast(8. / 14.));
+        autocorr_normalized(&[Fast(0.); 3], 3, &mut out);
+        assert_eq!(out, [Fast(0.); 4]);
+        // the energy is subnormal
+        let mut out = [Fast(0f32); 2];
+        autocorr_normalized(&[Fast(1e-20f32), Fast(1e-20)], 1, &mut out);
+        assert_eq!(out[0], Fast(1.));
+        assert!((out[1].get() - 0.5).abs() < 1e-3, "{:?}", out);
+    }
+
+    #[test]
+    fn envelope() {
+        let mut env = EnvelopeFollower::new(Fast(0.5f64), Fast(0.25));
+        let mut out = [Fast(0.); 4];
+        env.process(&[Fast(2.), Fast(-2.), Fast(0.), Fast(0.)], &mut out);
+        assert_eq!(out, [1., 1.5, 1.125, 0.84375].map(Fast));
+        env.reset();
+        assert_eq!(env.value(), Fast(0.));
+
+        let mut env = EnvelopeFollower::from_times(Fast(0.), Fast(0.01), Fast(1000f64));
+        env.push(Fast(1.));
+        assert_eq!(env.value(), Fast(1.));
+        env.extend([Fast(0.); 10]);
+        assert!((env.value().get() - (-1f64).exp()).abs() < 1e-12);
+    }
+
+    #[test]
+    fn gain() {
+        assert!((db_to_gain(Fast(20.)) - 10.).abs() < Fast(1e-5));
+        assert!((db_to_gain(Fast(-6.0206)) - 0.5).abs() < Fast(1e-5));
+        assert!((gain_to_db(Fast(0.1f32)) + 20.).abs() < Fast(1e-4));
+        assert!(gain_to_db(Fast(0f32)).get().is_finite());
+        let mut xs = [Fast(1.), Fast(-2.)];
+        apply_gain(&mut xs, Fast(0.5));
+        assert_eq!(xs, [Fast(0.5), Fast(-1.)]);
+        apply_gain_db(&mut xs, Fast(40.));
+        assert!((xs[1] + 100.).abs() < Fast(1e-4));
+
+        let mut xs = [Fast(0.25f64), Fast(-0.5), Fast(0.125)];
+        assert_eq!(limit_peak(&mut xs, Fast(0.5)), Fast(1.));
+        assert_eq!(normalize_peak(&mut xs, Fast(1.)), Fast(2.));
+        assert_eq!(xs, [Fast(0.5), Fast(-1.), Fast(0.25)]);
+        assert_eq!(limit_peak(&mut xs, Fast(0.5)), Fast(0.5));
+        assert_eq!(xs[1], Fast(-0.5));
+        assert_eq!(normalize_peak(&mut [Fast(0f32); 4], Fast(1.)), Fast(1.));
+        let mut tail = [Fast(1e-40f32), Fast(-5e-41)];
+        assert_eq!(normalize_peak(&mut tail, Fast(1.)), Fast(1.));
+        assert_eq!(tail, [Fast(1e-40f32), Fast(-5e-41)]);
+        // a subnormal peak with a representable gain
+        let mut tail = [Fast(0.), Fast(f32::MIN_POSITIVE / 2.)];
+        assert_eq!(normalize_peak(&mut tail, Fast(2f32.powi(-100))), Fast(2f32.powi(27)));
+    }
+
+    #[test]
+    fn resample() {
+        let input: [Fast<f64>; 7] = std::array::from_fn(|i| Fast(i as f64 * 3.));
+        // downsample by 2/3: a step of 1.5 input samples
+        let resampler = Resampler::new(Fast(2. / 3.));
+        let mut output = [Fast(0.); 5];
+        assert_eq!(resampler.output_len(input.len()), 5);
+        resampler.resample(&input, &mut output);
+        for (i, y) in output.iter().enumerate() {
+            assert!((y.get() - 4.5 * i as f64).abs() < 1e-12);
+        }
+        let mut output = [Fast(0.); 2];
+        resampler.resample(&input[..1], &mut output);
+        assert_eq!(output, [Fast(0.); 2]);
+        // a step far beyond usize::MAX
+        let mut output = [Fast(1.); 3];
+        Resampler::new(Fast(1e-30)).resample(&input, &mut output);
+        assert_eq!(output, [Fast(0.), Fast(18.), Fast(18.)]);
+        let subnormal = Resampler::new(Fast(1e-310));
+        assert_eq!(subnormal.step(), f64::MAX);
+        subnormal.resample(&input, &mut output);
+        assert_eq!(output, [Fast(0.), Fast(18.), Fast(18.)]);
+        // a step so small that the output length saturates
+        assert_eq!(Resampler::new(Fast(1e30)).output_len(10), usize::MAX);
+    }
+
+    #[test]
+    fn goertzel() {
+        // a cosine at bin 3 of 32 samples, and a tone at another bin
+        let x: [Fast<f64>; 32] = std::array::from_fn(|i| {
+            Fast((TAU * 3. * i as f64 / 32.).cos() + (TAU * 5. * i as f64 / 32.).sin())
+        });
+        let mut g = Goertzel::new(Fast(3.), Fast(32.));
+        g.extend(x);
+        assert!((g.magnitude().get() - 16.).abs() < 1e-9);
+        let mut g5 = Goertzel::new(Fast(5.), Fast(32.));
+        g5.extend(x);
+        assert!((g5.power() - g5.bin().norm_sqr()).abs() < Fast(1e-9));
+        g.reset();
+        assert_eq!(g.power(), Fast(0.));
+    }
+
+    #[test]
+    fn convolution() {
+        let a = [Fast(1.), Fast(2.), Fast(3.), Fast(4.)];
+        let b = [Fast(1.), Fast(-1.)];
+        let mut full = [Fast(0.); 5];
+        convolve(&a, &b, ConvMode::Full, &mut full);
+        assert_eq!(full, [1., 1., 1., 1., -4.].map(Fast));
+        // the same, with the arguments swapped
+        convolve(&b, &a, ConvMode::Full, &mut full);
+        assert_eq!(full, [1., 1., 1., 1., -4.].map(Fast));
+        let mut same = [Fast(0.); 4];
+        convolve(&a, &b, ConvMode::Same, &mut same);
+        assert_eq!(same, [1., 1., 1., 1.].map(Fast));
+        let mut valid = [Fast(0.); 3];
+        convolve(&a, &b, ConvMode::Valid, &mut valid);
+        assert_eq!(valid, [1., 1., 1.].map(Fast));
+        let mut same = [Fast(0.); 4];
+        convolve(&a, &[Fast(1.), Fast(2.), Fast(-1.)], ConvMode::Same, &mut same);
+        assert_eq!(same, [4., 6., 8., 5.].map(Fast));
+        assert_eq!(ConvMode::Valid.output_len(0, 3), 0);
+        convolve::<f64>(&[], &[], ConvMode::Full, &mut []);
+    }
+}
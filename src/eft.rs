@@ -0,0 +1,76 @@
+//! Error-free transformations: the rounded result of an operation together with its exact
+//! rounding error.
+//!
+//! These are the building blocks of compensated algorithms such as
+//! [`slice::dot2`](crate::slice::dot2). The arithmetic is strict, since the error terms are
+//! only exact if every operation is rounded as written; the relation `a + b = s + e` holds
+//! exactly unless an operation overflows.
+//!
+//! ```
+//! use fast_floats::{eft, Fast};
+//!
+//! let f = |x| unsafe { Fast::new(x) };
+//! let (s, e) = eft::two_sum(f(1e16), f(1.));
+//! assert_eq!((s, e), (f(1e16), f(1.)));
+//! ```
+
+use crate::{Fast, FastFloat};
+
+/// Return `s = a + b` rounded and its rounding error `e`, so that `a + b = s + e` exactly.
+///
+/// This is Knuth's TwoSum, six operations without branches.
+#[inline]
+pub fn two_sum<F: FastFloat>(a: Fast<F>, b: Fast<F>) -> (Fast<F>, Fast<F>) {
+    let (a, b) = (a.0, b.0);
+    let s = F::add_strict(a, b);
+    let bb = F::sub_strict(s, a);
+    let e = F::add_strict(F::sub_strict(a, F::sub_strict(s, bb)), F::sub_strict(b, bb));
+    (Fast(s), Fast(e))
+}
+
+/// Return `s = a + b` rounded and its rounding error `e`, given `|a| >= |b|`.
+///
+/// This is Dekker's FastTwoSum, three operations; the error is only exact if `a` is at
+/// least as large as `b` in magnitude (or zero), see [`two_sum`] otherwise.
+#[inline]
+pub fn fast_two_sum<F: FastFloat>(a: Fast<F>, b: Fast<F>) -> (Fast<F>, Fast<F>) {
+    debug_assert!(a.0.abs() >= b.0.abs() || a.0 == F::ZERO,
+                  "fast_two_sum: |a| must be at least |b|");
+    let s = F::add_strict(a.0, b.0);
+    let e = F::sub_strict(b.0, F::sub_strict(s, a.0));
+    (Fast(s), Fast(e))
+}
+
+/// Return `p = a * b` rounded and its rounding error `e`, so that `a * b = p + e` exactly.
+///
+/// The error is computed with a fused multiply-add, which is a library call where FMA is
+/// not supported in hardware. It is exact unless the product underflows.
+#[inline]
+pub fn two_prod<F: FastFloat>(a: Fast<F>, b: Fast<F>) -> (Fast<F>, Fast<F>) {
+    let p = F::mul_strict(a.0, b.0);
+    (Fast(p), Fast(a.0.mul_add(b.0, -p)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn transformations() {
+        let (a, b) = (Fast(1f32), Fast(3e-8f32));
+        let (s, e) = two_sum(a, b);
+        assert_eq!(s, a);
+        assert_eq!(e, b);
+        assert_eq!(two_sum(b, a), (s, e));
+        assert_eq!(fast_two_sum(a, b), (s, e));
+
+        let (s, e) = two_sum(Fast(0.1f64), Fast(0.2));
+        assert_eq!(s, Fast(0.1 + 0.2));
+        assert_eq!(e, Fast(-2f64.powi(-55)));
+
+        // 4097² = 2²⁴ + 2¹³ + 1, and the ulp of 2²⁴ in f32 is 2
+        let (p, e) = two_prod(Fast(4097f32), Fast(4097.));
+        assert_eq!(p.get() as f64 + e.get() as f64, 4097. * 4097.);
+        assert_eq!(e.get().abs(), 1.);
+    }
+}
@@ -0,0 +1,237 @@
+//! In-place radix-2 FFT over [`Complex`] values.
+//!
+//! The transforms are iterative decimation-in-time Cooley–Tukey, using the butterflies from
+//! [`complex`](crate::complex). The forward transform uses the exponent sign -1; the inverse
+//! transform is scaled by `1/n`, so that it inverts the forward transform.
+//!
+//! [`fft`] and [`ifft`] compute the twiddle factors on the fly (in `f64`, by recurrence).
+//! With the `alloc` feature, [`FftPlan`] precomputes a more accurate table of twiddle factors
+//! for a given length and reuses it between transforms.
+
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+use std::intrinsics::sqrtf64;
+
+use crate::complex::{butterfly2, Complex};
+use crate::{Fast, FastFloat};
+
+/// Compute the forward FFT of `data` in place.
+///
+/// ***Panics*** if the length of `data` is neither zero nor a power of two.
+pub fn fft<F: FastFloat>(data: &mut [Complex<F>]) {
+    transform(data, false);
+}
+
+/// Compute the inverse FFT of `data` in place, scaled by `1/n`.
+///
+/// ***Panics*** if the length of `data` is neither zero nor a power of two.
+pub fn ifft<F: FastFloat>(data: &mut [Complex<F>]) {
+    transform(data, true);
+    scale(data);
+}
+
+/// Return `exp(-2πi / len)` for a power of two `len >= 2`, as `(cos, sin)`.
+///
+/// Computed by repeated half-angle steps, which need only square roots.
+fn root_of_unity(len: usize) -> (f64, f64) {
+    debug_assert!(len.is_power_of_two() && len >= 2);
+    if len == 2 {
+        return (-1., 0.);
+    }
+    let (mut c, mut s) = (0., -1.);
+    let mut n = 4;
+    while n < len {
+        // half angle: cos(θ/2) = √((1 + cos θ) / 2), sin(θ/2) = sin θ / (2 cos(θ/2))
+        let ch = sqrtf64((1. + c) / 2.);
+        s /= 2. * ch;
+        c = ch;
+        n *= 2;
+    }
+    (c, s)
+}
+
+fn check_len(n: usize) {
+    assert!(n <= 1 || n.is_power_of_two(), "fft: length must be a power of two, got {}", n);
+}
+
+fn bit_reverse<F>(data: &mut [Complex<F>]) {
+    let n = data.len();
+    if n <= 2 {
+        return;
+    }
+    let shift = usize::BITS - n.trailing_zeros();
+    for i in 0..n {
+        let j = i.reverse_bits() >> shift;
+        if i < j {
+            data.swap(i, j);
+        }
+    }
+}
+
+fn complex<F: FastFloat>((re, im): (f64, f64)) -> Complex<F> {
+    Complex::new(Fast(F::from_f64(re)), Fast(F::from_f64(im)))
+}
+
+fn scale<F: FastFloat>(data: &mut [Complex<F>]) {
+    if data.len() > 1 {
+        let k = Fast(F::from_f64(1. / data.len() as f64));
+        for x in data {
+            *x = *x * k;
+        }
+    }
+}
+
+fn transform<F: FastFloat>(data: &mut [Complex<F>], inverse: bool) {
+    let n = data.len();
+    check_len(n);
+    bit_reverse(data);
+    let mut len = 2;
+    while len <= n {
+        let half = len / 2;
+        let (wr, wi) = root_of_unity(len);
+        let wi = if inverse { -wi } else { wi };
+        let (mut ur, mut ui) = (1., 0.);
+        for j in 0..half {
+            let w = complex((ur, ui));
+            for block in data.chunks_exact_mut(len) {
+                let (a, b) = butterfly2(block[j], block[j + half], w);
+                block[j] = a;
+                block[j + half] = b;
+            }
+            (ur, ui) = (ur * wr - ui * wi, ur * wi + ui * wr);
+        }
+        len *= 2;
+    }
+}
+
+/// A precomputed table of twiddle factors for FFTs of one length.
+///
+/// Each twiddle factor is a product of at most log₂ n roots of unity, so its error grows
+/// with log n instead of n as for the on the fly computation in [`fft`].
+#[cfg(feature = "alloc")]
+#[derive(Clone, Debug)]
+pub struct FftPlan<F> {
+    // exp(-2πik / n) for k in 0..n/2
+    twiddles: Vec<Complex<F>>,
+    len: usize,
+}
+
+#[cfg(feature = "alloc")]
+impl<F: FastFloat> FftPlan<F> {
+    /// Create a plan for transforms of length `len`.
+    ///
+    /// ***Panics*** if `len` is neither zero nor a power of two.
+    pub fn new(len: usize) -> Self {
+        check_len(len);
+        if len <= 1 {
+            return FftPlan { twiddles: Vec::new(), len };
+        }
+        let roots: Vec<_> = (0..len.trailing_zeros()).map(|b| root_of_unity(len >> b)).collect();
+        let twiddles = (0..len / 2).map(|k| {
+            let (mut c, mut s) = (1., 0.);
+            for (b, &(rc, rs)) in roots.iter().enumerate() {
+                if k & (1 << b) != 0 {
+                    (c, s) = (c * rc - s * rs, c * rs + s * rc);
+                }
+            }
+            complex((c, s))
+        }).collect();
+        FftPlan { twiddles, len }
+    }
+
+    /// Return the transform length of the plan
+    pub fn len(&self) -> usize { self.len }
+
+    /// Return `true` if the transform length is zero
+    pub fn is_empty(&self) -> bool { self.len == 0 }
+
+    /// Compute the forward FFT of `data` in place.
+    ///
+    /// ***Panics*** if the length of `data` is not the length of the plan.
+    pub fn forward(&self, data: &mut [Complex<F>]) {
+        self.transform(data, false);
+    }
+
+    /// Compute the inverse FFT of `data` in place, scaled by `1/n`.
+    ///
+    /// ***Panics*** if the length of `data` is not the length of the plan.
+    pub fn inverse(&self, data: &mut [Complex<F>]) {
+        self.transform(data, true);
+        scale(data);
+    }
+
+    fn transform(&self, data: &mut [Complex<F>], inverse: bool) {
+        assert_eq!(data.len(), self.len, "FftPlan: data length must equal the plan length");
+        bit_reverse(data);
+        let n = self.len;
+        let mut len = 2;
+        while len <= n {
+            let half = len / 2;
+            let stride = n / len;
+            for block in data.chunks_exact_mut(len) {
+                let (lo, hi) = block.split_at_mut(half);
+                for (j, (a, b)) in lo.iter_mut().zip(hi).enumerate() {
+                    let w = self.twiddles[j * stride];
+                    let w = if inverse { w.conj() } else { w };
+                    (*a, *b) = butterfly2(*a, *b, w);
+                }
+            }
+            len *= 2;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn c(re: f64, im: f64) -> Complex<f64> { Complex::new(Fast(re), Fast(im)) }
+
+    fn assert_close(a: &[Complex<f64>], b: &[Complex<f64>]) {
+        for (x, y) in a.iter().zip(b) {
+            assert!((*x - *y).norm_sqr().get() < 1e-20, "{:?} != {:?}", x, y);
+        }
+    }
+
+    #[test]
+    fn roundtrip() {
+        let x: [Complex<f64>; 16] = std::array::from_fn(|i| c(i as f64, 1. - i as f64 / 4.));
+        let mut y = x;
+        fft(&mut y);
+        // compare with the definition
+        for (k, &yk) in y.iter().enumerate() {
+            let mut sum = c(0., 0.);
+            for (j, &xj) in x.iter().enumerate() {
+                let (cos, sin) = root_of_unity(16);
+                let (mut wr, mut wi) = (1., 0.);
+                for _ in 0..(j * k) % 16 {
+                    (wr, wi) = (wr * cos - wi * sin, wr * sin + wi * cos);
+                }
+                sum = sum + xj * c(wr, wi);
+            }
+            assert_close(&[yk], &[sum]);
+        }
+        ifft(&mut y);
+        assert_close(&y, &x);
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn plan() {
+        let plan = FftPlan::new(32);
+        let x: [Complex<f64>; 32] = std::array::from_fn(|i| c((i % 5) as f64, (i % 3) as f64));
+        let (mut y, mut z) = (x, x);
+        fft(&mut y);
+        plan.forward(&mut z);
+        assert_close(&y, &z);
+        plan.inverse(&mut z);
+        assert_close(&z, &x);
+
+        let mut one = [c(2., 1.)];
+        FftPlan::new(1).forward(&mut one);
+        assert_eq!(one, [c(2., 1.)]);
+        let empty = FftPlan::<f64>::new(0);
+        assert!(empty.is_empty());
+        empty.inverse(&mut []);
+    }
+}
@@ -0,0 +1,397 @@
+use std::cmp::Ordering;
+use std::fmt;
+use std::num::FpCategory;
+use std::intrinsics::{self, cosf64, floorf32, floorf64, fmaf32, fmaf64, logf64, powf64,
+                      round_ties_even_f32, round_ties_even_f64, sinf64, sqrtf64};
+use std::ops::Neg;
+
+use self::f32_math::{cosf32, logf32, powf32, sinf32, sqrtf32};
+use crate::Fast;
+
+/// The intrinsics of the arithmetic operators
+#[cfg(not(feature = "algebraic"))]
+mod arith {
+    pub(super) use std::intrinsics::{fadd_fast as fadd, fdiv_fast as fdiv, fmul_fast as fmul,
+                                     frem_fast as frem, fsub_fast as fsub};
+}
+
+/// The intrinsics of the arithmetic operators, with only the value-safe flags
+#[cfg(feature = "algebraic")]
+mod arith {
+    pub(super) use std::intrinsics::{fadd_algebraic as fadd, fdiv_algebraic as fdiv,
+                                     fmul_algebraic as fmul, frem_algebraic as frem,
+                                     fsub_algebraic as fsub};
+}
+
+/// The math functions of `f32`
+#[cfg(not(feature = "micromath"))]
+mod f32_math {
+    pub(super) use std::intrinsics::{cosf32, logf32, powf32, sinf32, sqrtf32};
+}
+
+/// The math functions of `f32`, using the approximations of `micromath`
+#[cfg(feature = "micromath")]
+mod f32_math {
+    use micromath::F32Ext;
+
+    #[inline(always)]
+    pub(super) fn cosf32(x: f32) -> f32 { F32Ext::cos(x) }
+    #[inline(always)]
+    pub(super) fn logf32(x: f32) -> f32 { F32Ext::ln(x) }
+    #[inline(always)]
+    pub(super) fn powf32(x: f32, n: f32) -> f32 { F32Ext::powf(x, n) }
+    #[inline(always)]
+    pub(super) fn sinf32(x: f32) -> f32 { F32Ext::sin(x) }
+    #[inline(always)]
+    pub(super) fn sqrtf32(x: f32) -> f32 {
+        // the approximation of zero is not zero
+        if x == 0. { x } else { F32Ext::sqrt(x) }
+    }
+}
+
+/// The float types that `Fast` supports: `f32` and `f64`.
+///
+/// This trait is sealed and can not be implemented outside this crate. It is used as the
+/// bound for everything in the crate that is generic over the float type.
+pub trait FastFloat: Copy + PartialEq + PartialOrd + Neg<Output = Self> + fmt::Debug + sealed::Sealed {
+    /// Zero (`0.0`)
+    const ZERO: Self;
+    /// One (`1.0`)
+    const ONE: Self;
+    /// The unsigned integer type of the same size, which holds the bits: `u32` or `u64`
+    type Bits: Copy + Eq + Ord + fmt::Debug + fmt::LowerHex + fmt::UpperHex;
+}
+
+pub(crate) mod sealed {
+    use std::cmp::Ordering;
+    use std::num::FpCategory;
+
+    use crate::{Fast, FastFloat};
+
+    /// Per-type hooks for the crate internals: the intrinsics, and the best slice kernel
+    /// for each type.
+    pub trait Sealed: Copy {
+        unsafe fn fadd_fast(a: Self, b: Self) -> Self;
+        unsafe fn fsub_fast(a: Self, b: Self) -> Self;
+        unsafe fn fmul_fast(a: Self, b: Self) -> Self;
+        unsafe fn fdiv_fast(a: Self, b: Self) -> Self;
+        unsafe fn frem_fast(a: Self, b: Self) -> Self;
+        /// Add without fast-math flags, so that the compiler can not reassociate
+        fn add_strict(a: Self, b: Self) -> Self;
+        /// Subtract without fast-math flags
+        fn sub_strict(a: Self, b: Self) -> Self;
+        /// Multiply without fast-math flags, so that the compiler can not contract to FMA
+        fn mul_strict(a: Self, b: Self) -> Self;
+        /// Divide without fast-math flags
+        fn div_strict(a: Self, b: Self) -> Self;
+
+        fn sqrt(self) -> Self;
+        /// Square root, correctly rounded also with the `micromath` feature
+        fn sqrt_strict(self) -> Self;
+        fn mul_add(self, a: Self, b: Self) -> Self;
+        fn powf(self, n: Self) -> Self;
+        fn ln(self) -> Self;
+        fn sin(self) -> Self;
+        fn cos(self) -> Self;
+        /// Sine and cosine computed in `f64`, also with the `micromath` feature
+        fn sin_cos_strict(self) -> (Self, Self);
+        #[cfg(feature = "libm")]
+        fn atan(self) -> Self;
+        #[cfg(feature = "libm")]
+        fn atan2(self, other: Self) -> Self;
+        #[cfg(feature = "libm")]
+        fn asin(self) -> Self;
+        #[cfg(feature = "libm")]
+        fn acos(self) -> Self;
+        #[cfg(feature = "libm")]
+        fn sinh(self) -> Self;
+        #[cfg(feature = "libm")]
+        fn cosh(self) -> Self;
+        #[cfg(feature = "libm")]
+        fn tanh(self) -> Self;
+        fn abs(self) -> Self;
+        fn min(self, other: Self) -> Self;
+        fn max(self, other: Self) -> Self;
+        fn floor(self) -> Self;
+        fn round_ties_even(self) -> Self;
+        fn copysign(self, sign: Self) -> Self;
+        /// Return `a` if `mask` is true and otherwise `b`, with bit operations
+        fn select(mask: bool, a: Self, b: Self) -> Self;
+
+        fn is_nan(self) -> bool;
+        fn is_infinite(self) -> bool;
+        fn is_finite(self) -> bool;
+        fn is_normal(self) -> bool;
+        fn is_sign_positive(self) -> bool;
+        fn is_sign_negative(self) -> bool;
+        fn classify(self) -> FpCategory;
+
+        fn to_bits(self) -> <Self as FastFloat>::Bits where Self: FastFloat;
+        fn from_bits(bits: <Self as FastFloat>::Bits) -> Self where Self: FastFloat;
+        fn next_up(self) -> Self;
+        fn next_down(self) -> Self;
+        /// Map the bits to an integer that is ordered like the floats, with equal
+        /// values for both zeros and consecutive values for consecutive floats.
+        fn ordered_bits(self) -> i64;
+        fn total_cmp(&self, other: &Self) -> Ordering;
+
+        /// Convert with `as`
+        fn from_f64(x: f64) -> Self;
+        /// Convert with `as` (saturating, NaN is zero)
+        fn to_usize(self) -> usize;
+        /// Convert with `as`
+        fn to_f64(self) -> f64;
+        /// Convert with `as` (saturating, NaN is zero)
+        fn to_i32(self) -> i32;
+        /// Return `2^k`; `k` must be in the range of normal exponents.
+        fn exp2i(k: i32) -> Self;
+        /// `exp(x)` is finite and normal for `x` in `EXP_MIN_ARG..=EXP_MAX_ARG`, with some margin
+        const EXP_MIN_ARG: Self;
+        const EXP_MAX_ARG: Self;
+        /// Split a finite value into `(m, e)` such that `self = m 2^e` and `0.5 <= |m| < 1`;
+        /// zero is returned as `(0, 0)`.
+        fn frexp(self) -> (Self, i32);
+        /// Like `frexp`, without branches, for positive normal values only
+        fn frexp_normal(self) -> (Self, i32);
+        /// The smallest positive normal value
+        const MIN_POSITIVE: Self;
+        /// The largest finite value
+        const MAX: Self;
+        /// The difference between one and the next larger value
+        const EPSILON: Self;
+
+        fn sum_kernel(xs: &[Fast<Self>]) -> Fast<Self>;
+        fn dot_kernel(xs: &[Fast<Self>], ys: &[Fast<Self>]) -> Fast<Self>;
+        fn axpy_kernel(a: Fast<Self>, xs: &[Fast<Self>], ys: &mut [Fast<Self>]);
+    }
+}
+
+macro_rules! impl_float {
+    ($($t:ident, $u:ident, $i:ident, $bits:expr, $sqrt:ident, $sqrt_strict:path, $fma:ident, $pow:ident, $ln:ident, $sin:ident, $cos:ident, $floor:ident, $round:ident, $exp_min:expr, $exp_max:expr, $sum:ident, $dot:ident, $axpy:ident;)*) => {
+        $(
+        impl FastFloat for $t {
+            const ZERO: Self = 0.;
+            const ONE: Self = 1.;
+            type Bits = $u;
+        }
+
+        impl sealed::Sealed for $t {
+            #[inline(always)]
+            unsafe fn fadd_fast(a: Self, b: Self) -> Self { arith::fadd(a, b) }
+            #[inline(always)]
+            unsafe fn fsub_fast(a: Self, b: Self) -> Self { arith::fsub(a, b) }
+            #[inline(always)]
+            unsafe fn fmul_fast(a: Self, b: Self) -> Self { arith::fmul(a, b) }
+            #[inline(always)]
+            unsafe fn fdiv_fast(a: Self, b: Self) -> Self { arith::fdiv(a, b) }
+            #[inline(always)]
+            unsafe fn frem_fast(a: Self, b: Self) -> Self { arith::frem(a, b) }
+            #[inline(always)]
+            fn add_strict(a: Self, b: Self) -> Self { a + b }
+            #[inline(always)]
+            fn sub_strict(a: Self, b: Self) -> Self { a - b }
+            #[inline(always)]
+            fn mul_strict(a: Self, b: Self) -> Self { a * b }
+            #[inline(always)]
+            fn div_strict(a: Self, b: Self) -> Self { a / b }
+
+            #[inline(always)]
+            fn sqrt(self) -> Self { $sqrt(self) }
+            #[inline(always)]
+            fn sqrt_strict(self) -> Self { $sqrt_strict(self) }
+            #[inline(always)]
+            fn mul_add(self, a: Self, b: Self) -> Self { $fma(self, a, b) }
+            #[inline(always)]
+            fn powf(self, n: Self) -> Self { $pow(self, n) }
+            #[inline(always)]
+            fn ln(self) -> Self { $ln(self) }
+            #[inline(always)]
+            fn sin(self) -> Self { $sin(self) }
+            #[inline(always)]
+            fn cos(self) -> Self { $cos(self) }
+            #[inline]
+            fn sin_cos_strict(self) -> (Self, Self) {
+                let x = self as f64;
+                (sinf64(x) as $t, cosf64(x) as $t)
+            }
+            #[cfg(feature = "libm")]
+            #[inline]
+            fn atan(self) -> Self { libm::Libm::<$t>::atan(self) }
+            #[cfg(feature = "libm")]
+            #[inline]
+            fn atan2(self, other: Self) -> Self { libm::Libm::<$t>::atan2(self, other) }
+            #[cfg(feature = "libm")]
+            #[inline]
+            fn asin(self) -> Self { libm::Libm::<$t>::asin(self) }
+            #[cfg(feature = "libm")]
+            #[inline]
+            fn acos(self) -> Self { libm::Libm::<$t>::acos(self) }
+            #[cfg(feature = "libm")]
+            #[inline]
+            fn sinh(self) -> Self { libm::Libm::<$t>::sinh(self) }
+            #[cfg(feature = "libm")]
+            #[inline]
+            fn cosh(self) -> Self { libm::Libm::<$t>::cosh(self) }
+            #[cfg(feature = "libm")]
+            #[inline]
+            fn tanh(self) -> Self { libm::Libm::<$t>::tanh(self) }
+            #[inline(always)]
+            fn abs(self) -> Self { intrinsics::fabs(self) }
+            #[inline(always)]
+            fn min(self, other: Self) -> Self { <$t>::min(self, other) }
+            #[inline(always)]
+            fn max(self, other: Self) -> Self { <$t>::max(self, other) }
+            #[inline(always)]
+            fn floor(self) -> Self { $floor(self) }
+            #[inline(always)]
+            fn round_ties_even(self) -> Self { $round(self) }
+            #[inline(always)]
+            fn copysign(self, sign: Self) -> Self { <$t>::copysign(self, sign) }
+            #[inline(always)]
+            fn select(mask: bool, a: Self, b: Self) -> Self {
+                let m = (mask as $u).wrapping_neg();
+                <$t>::from_bits((a.to_bits() & m) | (b.to_bits() & !m))
+            }
+
+            #[inline(always)]
+            fn is_nan(self) -> bool { <$t>::is_nan(self) }
+            #[inline(always)]
+            fn is_infinite(self) -> bool { <$t>::is_infinite(self) }
+            #[inline(always)]
+            fn is_finite(self) -> bool { <$t>::is_finite(self) }
+            #[inline(always)]
+            fn is_normal(self) -> bool { <$t>::is_normal(self) }
+            #[inline(always)]
+            fn is_sign_positive(self) -> bool { <$t>::is_sign_positive(self) }
+            #[inline(always)]
+            fn is_sign_negative(self) -> bool { <$t>::is_sign_negative(self) }
+            #[inline(always)]
+            fn classify(self) -> FpCategory { <$t>::classify(self) }
+
+            #[inline(always)]
+            fn to_bits(self) -> $u { <$t>::to_bits(self) }
+            #[inline(always)]
+            fn from_bits(bits: $u) -> Self { <$t>::from_bits(bits) }
+            #[inline(always)]
+            fn next_up(self) -> Self { <$t>::next_up(self) }
+            #[inline(always)]
+            fn next_down(self) -> Self { <$t>::next_down(self) }
+            #[inline]
+            fn ordered_bits(self) -> i64 {
+                let i = self.to_bits() as $i;
+                (if i < 0 { <$i>::MIN - i } else { i }) as i64
+            }
+            #[inline(always)]
+            fn total_cmp(&self, other: &Self) -> Ordering { <$t>::total_cmp(self, other) }
+
+            #[inline(always)]
+            fn from_f64(x: f64) -> Self { x as $t }
+            #[inline(always)]
+            fn to_usize(self) -> usize { self as usize }
+            #[inline(always)]
+            fn to_f64(self) -> f64 { self as f64 }
+            #[inline(always)]
+            fn to_i32(self) -> i32 { self as i32 }
+            #[inline(always)]
+            fn exp2i(k: i32) -> Self {
+                debug_assert!(k > <$t>::MIN_EXP - 2 && k < <$t>::MAX_EXP);
+                <$t>::from_bits(((k + <$t>::MAX_EXP - 1) as $u) << (<$t>::MANTISSA_DIGITS - 1))
+            }
+            const EXP_MIN_ARG: Self = $exp_min;
+            const EXP_MAX_ARG: Self = $exp_max;
+            #[inline]
+            fn frexp(self) -> (Self, i32) {
+                if !self.is_normal() {
+                    if self == 0. {
+                        return (self, 0);
+                    }
+                    // subnormal: scale into the normal range first
+                    let mant_bits = <$t>::MANTISSA_DIGITS as i32 - 1;
+                    let (m, e) = (self * Self::exp2i(mant_bits)).frexp_normal();
+                    return (m, e - mant_bits);
+                }
+                self.frexp_normal()
+            }
+            #[inline(always)]
+            fn frexp_normal(self) -> (Self, i32) {
+                const MANT_BITS: u32 = <$t>::MANTISSA_DIGITS - 1;
+                const EXP_MASK: $u = ((1 << ($bits - 1 - MANT_BITS)) - 1) << MANT_BITS;
+                const BIAS: i32 = <$t>::MAX_EXP - 2;
+                let bits = self.to_bits();
+                let exp = ((bits & EXP_MASK) >> MANT_BITS) as i32;
+                (<$t>::from_bits((bits & !EXP_MASK) | ((BIAS as $u) << MANT_BITS)), exp - BIAS)
+            }
+            const MIN_POSITIVE: Self = <$t>::MIN_POSITIVE;
+            const MAX: Self = <$t>::MAX;
+            const EPSILON: Self = <$t>::EPSILON;
+
+            #[inline]
+            fn sum_kernel(xs: &[Fast<Self>]) -> Fast<Self> {
+                #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+                if crate::arch::x86::is_avx_fma_supported() {
+                    unsafe {
+                        return crate::arch::x86::$sum(xs);
+                    }
+                }
+                #[cfg(all(target_arch = "aarch64", target_feature = "neon"))]
+                unsafe {
+                    return crate::arch::aarch64::$sum(xs);
+                }
+                #[cfg(all(target_arch = "wasm32", target_feature = "simd128"))]
+                unsafe {
+                    return crate::arch::wasm32::$sum(xs);
+                }
+                #[allow(unreachable_code)]
+                crate::slice::portable::sum(xs)
+            }
+
+            #[inline]
+            fn dot_kernel(xs: &[Fast<Self>], ys: &[Fast<Self>]) -> Fast<Self> {
+                #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+                if crate::arch::x86::is_avx_fma_supported() {
+                    unsafe {
+                        return crate::arch::x86::$dot(xs, ys);
+                    }
+                }
+                #[cfg(all(target_arch = "aarch64", target_feature = "neon"))]
+                unsafe {
+                    return crate::arch::aarch64::$dot(xs, ys);
+                }
+                #[cfg(all(target_arch = "wasm32", target_feature = "simd128"))]
+                unsafe {
+                    return crate::arch::wasm32::$dot(xs, ys);
+                }
+                #[allow(unreachable_code)]
+                crate::slice::portable::dot(xs, ys)
+            }
+
+            #[inline]
+            fn axpy_kernel(a: Fast<Self>, xs: &[Fast<Self>], ys: &mut [Fast<Self>]) {
+                #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+                if crate::arch::x86::is_avx_fma_supported() {
+                    unsafe {
+                        return crate::arch::x86::$axpy(a, xs, ys);
+                    }
+                }
+                #[cfg(all(target_arch = "aarch64", target_feature = "neon"))]
+                unsafe {
+                    return crate::arch::aarch64::$axpy(a, xs, ys);
+                }
+                #[cfg(all(target_arch = "wasm32", target_feature = "simd128"))]
+                unsafe {
+                    return crate::arch::wasm32::$axpy(a, xs, ys);
+                }
+                #[allow(unreachable_code)]
+                crate::slice::portable::axpy(a, xs, ys)
+            }
+        }
+        )*
+    }
+}
+
+impl_float! {
+    f32, u32, i32, 32, sqrtf32, intrinsics::sqrtf32, fmaf32, powf32, logf32, sinf32, cosf32,
+          floorf32, round_ties_even_f32, -87.33, 88.72, sum_f32, dot_f32, axpy_f32;
+    f64, u64, i64, 64, sqrtf64, sqrtf64, fmaf64, powf64, logf64, sinf64, cosf64, floorf64,
+          round_ties_even_f64, -708.39, 709.78, sum_f64, dot_f64, axpy_f64;
+}
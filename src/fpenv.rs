@@ -0,0 +1,222 @@
+//! Scoped control of the floating point environment.
+//!
+//! This module is available on x86_64, x86 with SSE, and aarch64. The control register
+//! (MXCSR on x86, FPCR on aarch64) is per thread, so the guards change the environment of
+//! the current thread only, and they are not `Send`.
+//!
+//! The compiler assumes the default floating point environment: it can evaluate float
+//! operations at compile time, or move them out of the scope of a guard, with the default
+//! behavior. This is why creating a guard is `unsafe`.
+
+use std::arch::asm;
+use std::marker::PhantomData;
+
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+mod imp {
+    use super::asm;
+
+    pub(super) type Control = u32;
+
+    /// MXCSR flush to zero (results) and denormals are zero (inputs)
+    pub(super) const FLUSH_DENORMALS: Control = 0x8040;
+    /// MXCSR rounding control
+    pub(super) const ROUNDING_MASK: Control = 0x6000;
+
+    pub(super) fn rounding(mode: super::Rounding) -> Control {
+        use super::Rounding::*;
+        match mode {
+            Nearest => 0x0000,
+            Down => 0x2000,
+            Up => 0x4000,
+            TowardZero => 0x6000,
+        }
+    }
+
+    #[inline]
+    pub(super) fn get() -> Control {
+        let mut csr: Control = 0;
+        unsafe {
+            asm!("stmxcsr [{}]", in(reg) &mut csr, options(nostack, preserves_flags));
+        }
+        csr
+    }
+
+    #[inline]
+    pub(super) unsafe fn set(csr: Control) {
+        asm!("ldmxcsr [{}]", in(reg) &csr, options(nostack, readonly, preserves_flags));
+    }
+}
+
+#[cfg(target_arch = "aarch64")]
+mod imp {
+    use super::asm;
+
+    pub(super) type Control = u64;
+
+    /// FPCR flush to zero, which flushes both inputs and results
+    pub(super) const FLUSH_DENORMALS: Control = 1 << 24;
+    /// FPCR rounding mode
+    pub(super) const ROUNDING_MASK: Control = 3 << 22;
+
+    pub(super) fn rounding(mode: super::Rounding) -> Control {
+        use super::Rounding::*;
+        match mode {
+            Nearest => 0 << 22,
+            Up => 1 << 22,
+            Down => 2 << 22,
+            TowardZero => 3 << 22,
+        }
+    }
+
+    #[inline]
+    pub(super) fn get() -> Control {
+        let fpcr: Control;
+        unsafe {
+            asm!("mrs {}, fpcr", out(reg) fpcr, options(nomem, nostack, preserves_flags));
+        }
+        fpcr
+    }
+
+    #[inline]
+    pub(super) unsafe fn set(fpcr: Control) {
+        asm!("msr fpcr, {}", in(reg) fpcr, options(nomem, nostack, preserves_flags));
+    }
+}
+
+/// A guard that flushes subnormal (denormal) values to zero until it is dropped.
+///
+/// Operations on subnormal values are much slower than on normal values on many CPUs.
+/// While the guard is alive, subnormal inputs of float operations are treated as zero and
+/// subnormal results are replaced by zero (FTZ and DAZ on x86, FZ on aarch64). Dropping the
+/// guard restores the previous setting.
+///
+/// ```
+/// use fast_floats::fpenv::DenormalsGuard;
+/// use std::hint::black_box;
+///
+/// let tiny = black_box(f64::MIN_POSITIVE);
+/// {
+///     let _guard = unsafe { DenormalsGuard::new() };
+///     assert_eq!(black_box(tiny) / black_box(4.), 0.);
+/// }
+/// assert_ne!(black_box(tiny) / black_box(4.), 0.);
+/// ```
+#[derive(Debug)]
+pub struct DenormalsGuard {
+    saved: imp::Control,
+    // the control register is per thread
+    not_send: PhantomData<*const ()>,
+}
+
+impl DenormalsGuard {
+    /// Flush subnormal values to zero until the guard is dropped.
+    ///
+    /// # Safety
+    ///
+    /// The compiler assumes the default floating point environment. The code in the scope
+    /// must be correct whether or not a float operation flushes subnormal values, for
+    /// example because it was evaluated at compile time or moved out of the scope.
+    pub unsafe fn new() -> Self {
+        let saved = imp::get();
+        imp::set(saved | imp::FLUSH_DENORMALS);
+        DenormalsGuard { saved, not_send: PhantomData }
+    }
+}
+
+impl Drop for DenormalsGuard {
+    fn drop(&mut self) {
+        unsafe { imp::set(self.saved) }
+    }
+}
+
+/// A rounding mode of float operations, see [`RoundingGuard`].
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Rounding {
+    /// Round to nearest, ties to even (the default)
+    Nearest,
+    /// Round toward positive infinity
+    Up,
+    /// Round toward negative infinity
+    Down,
+    /// Round toward zero
+    TowardZero,
+}
+
+/// A guard that sets the rounding mode of float operations until it is dropped.
+///
+/// Directed rounding brackets the rounding error of a computation: evaluating it rounding
+/// down and rounding up gives bounds of the exact result, when each operation is monotonic
+/// in its inputs. Dropping the guard restores the previous rounding mode.
+///
+/// ```
+/// use fast_floats::fpenv::{Rounding, RoundingGuard};
+/// use std::hint::black_box;
+///
+/// let third = |_guard: RoundingGuard| black_box(1f64) / black_box(3.);
+/// let lo = third(unsafe { RoundingGuard::new(Rounding::Down) });
+/// let hi = third(unsafe { RoundingGuard::new(Rounding::Up) });
+/// assert_eq!(lo.next_up(), hi);
+/// ```
+#[derive(Debug)]
+pub struct RoundingGuard {
+    saved: imp::Control,
+    // the control register is per thread
+    not_send: PhantomData<*const ()>,
+}
+
+impl RoundingGuard {
+    /// Set the rounding mode to `mode` until the guard is dropped.
+    ///
+    /// # Safety
+    ///
+    /// The compiler assumes the default floating point environment. The code in the scope
+    /// must be correct whether a float operation rounds with `mode` or to nearest, for
+    /// example because it was evaluated at compile time or moved out of the scope.
+    pub unsafe fn new(mode: Rounding) -> Self {
+        let saved = imp::get();
+        imp::set(saved & !imp::ROUNDING_MASK | imp::rounding(mode));
+        RoundingGuard { saved, not_send: PhantomData }
+    }
+}
+
+impl Drop for RoundingGuard {
+    fn drop(&mut self) {
+        unsafe { imp::set(self.saved) }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::hint::black_box;
+
+    #[test]
+    fn denormals() {
+        let tiny = black_box(f32::MIN_POSITIVE);
+        let before = imp::get();
+        {
+            let _guard = unsafe { DenormalsGuard::new() };
+            assert_eq!(black_box(tiny) / black_box(2.), 0.);
+            // subnormal inputs are zero, too
+            assert_eq!(black_box(f32::from_bits(1)) * black_box(2.), 0.);
+        }
+        assert_eq!(imp::get(), before);
+        assert_eq!(black_box(tiny) / black_box(2.), tiny / 2.);
+    }
+
+    #[test]
+    fn rounding() {
+        let before = imp::get();
+        let div = |mode| {
+            let _guard = unsafe { RoundingGuard::new(mode) };
+            (black_box(-1f32) / black_box(3.), black_box(1f32) / black_box(3.))
+        };
+        let (down_neg, down_pos) = div(Rounding::Down);
+        let (up_neg, up_pos) = div(Rounding::Up);
+        assert_eq!((up_neg, up_pos), (down_neg.next_up(), down_pos.next_up()));
+        assert_eq!(div(Rounding::TowardZero), (up_neg, down_pos));
+        // 1/3 is closer to the larger neighbor in f32
+        assert_eq!(div(Rounding::Nearest), (down_neg, up_pos));
+        assert_eq!(imp::get(), before);
+    }
+}
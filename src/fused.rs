@@ -0,0 +1,55 @@
+//! Compound assignments that are guaranteed to compute with a fused multiply-add.
+//!
+//! Each helper rounds only once, like [`Fast::mul_add`]. The regular operators allow the
+//! compiler to contract `a * b + c` to FMA, but do not guarantee it.
+//!
+//! ```
+//! use fast_floats::{fused, Fast};
+//!
+//! let f = |x| unsafe { Fast::new(x) };
+//! let mut acc = f(1.);
+//! fused::add_scaled(&mut acc, f(2.), f(3.));
+//! assert_eq!(acc, f(7.));
+//! ```
+
+use crate::{Fast, FastFloat};
+
+/// Compute `acc = acc + a * x`
+#[inline(always)]
+pub fn add_scaled<F: FastFloat>(acc: &mut Fast<F>, a: Fast<F>, x: Fast<F>) {
+    *acc = a.mul_add(x, *acc);
+}
+
+/// Compute `acc = acc - a * x`
+#[inline(always)]
+pub fn sub_scaled<F: FastFloat>(acc: &mut Fast<F>, a: Fast<F>, x: Fast<F>) {
+    *acc = (-a).mul_add(x, *acc);
+}
+
+/// Compute `acc = acc * a + b`, see [`Fast::mul_add_assign`]
+#[inline(always)]
+pub fn mul_add_assign<F: FastFloat>(acc: &mut Fast<F>, a: Fast<F>, b: Fast<F>) {
+    acc.mul_add_assign(a, b);
+}
+
+/// Compute `acc = acc + t * (target - acc)`, moving `acc` a fraction `t` towards `target`
+#[inline(always)]
+pub fn lerp_to<F: FastFloat>(acc: &mut Fast<F>, t: Fast<F>, target: Fast<F>) {
+    *acc = t.mul_add(target - *acc, *acc);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn assign() {
+        let mut acc = Fast(10.);
+        sub_scaled(&mut acc, Fast(2.), Fast(3.));
+        assert_eq!(acc, Fast(4.));
+        mul_add_assign(&mut acc, Fast(0.5), Fast(1.));
+        assert_eq!(acc, Fast(3.));
+        lerp_to(&mut acc, Fast(0.25), Fast(7.));
+        assert_eq!(acc, Fast(4.));
+    }
+}
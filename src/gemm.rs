@@ -0,0 +1,57 @@
+//! Matrix multiplication for buffers of fast values, using the `matrixmultiply` crate.
+//!
+//! This module requires the `matrixmultiply` feature. The buffers are passed to
+//! `matrixmultiply` as plain floats (`Fast<F>` is `repr(transparent)`), so the products are
+//! computed by its vectorized kernels with regular float semantics, not with the fast-math
+//! intrinsics.
+
+use crate::Fast;
+
+macro_rules! gemm {
+    ($($name:ident, $t:ident;)*) => {
+        $(
+        /// Compute the matrix product `c ← alpha a b + beta c`.
+        ///
+        /// `a`, `b` and `c` are dense, row major matrices of `m × k`, `k × n` and `m × n`
+        /// elements respectively. If `beta` is zero, the previous contents of `c` are ignored.
+        ///
+        /// ***Panics*** if a slice does not have the length of its matrix.
+        #[allow(clippy::too_many_arguments)]
+        pub fn $name(m: usize, k: usize, n: usize,
+                     alpha: Fast<$t>, a: &[Fast<$t>], b: &[Fast<$t>],
+                     beta: Fast<$t>, c: &mut [Fast<$t>])
+        {
+            assert_eq!(Some(a.len()), m.checked_mul(k), "gemm: a must have m × k elements");
+            assert_eq!(Some(b.len()), k.checked_mul(n), "gemm: b must have k × n elements");
+            assert_eq!(Some(c.len()), m.checked_mul(n), "gemm: c must have m × n elements");
+            // Safety: the checks above make every element in range of the row major strides;
+            // slice lengths are at most isize::MAX.
+            unsafe {
+                matrixmultiply::$name(m, k, n,
+                                      alpha.0, a.as_ptr() as *const $t, k as isize, 1,
+                                      b.as_ptr() as *const $t, n as isize, 1,
+                                      beta.0, c.as_mut_ptr() as *mut $t, n as isize, 1);
+            }
+        }
+        )*
+    }
+}
+
+gemm! {
+    sgemm, f32;
+    dgemm, f64;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn product() {
+        let a = [Fast(1.), Fast(2.), Fast(3.), Fast(4.), Fast(5.), Fast(6.)];
+        let mut c = [Fast(1.); 4];
+        dgemm(2, 3, 2, Fast(1.), &a, &[Fast(1.), Fast(0.), Fast(0.), Fast(1.), Fast(1.), Fast(1.)],
+              Fast(2.), &mut c);
+        assert_eq!(c, [Fast(6.), Fast(7.), Fast(12.), Fast(13.)]);
+    }
+}
@@ -0,0 +1,133 @@
+//! Conversion between `f32` and half precision (IEEE 754 binary16) storage.
+//!
+//! Half precision values are stored as their bits in `u16`. The conversions use only integer
+//! and plain float operations without branches on the values, so the loops vectorize.
+//!
+//! Conversion to half precision rounds to nearest, ties to even; values above the half
+//! precision range round to infinity, and small values to subnormals or zero. Conversion
+//! to `f32` is exact.
+//!
+//! ```
+//! use fast_floats::{half, Fast};
+//!
+//! let f = |x| unsafe { Fast::new(x) };
+//! let mut h = [0u16; 3];
+//! half::from_f32(&[f(1.), f(-2.), f(65504.)], &mut h);
+//! assert_eq!(h, [0x3c00, 0xc000, 0x7bff]);
+//!
+//! let mut x = [f(0.); 3];
+//! // safe because the values are finite
+//! unsafe { half::to_f32(&h, &mut x); }
+//! assert_eq!(x, [f(1.), f(-2.), f(65504.)]);
+//! ```
+
+use crate::Fast;
+
+const F32_EXP_BIAS: u32 = 127;
+const F16_EXP_BIAS: u32 = 15;
+const MANT_SHIFT: u32 = 23 - 10;
+// the smallest f32 that rounds to infinity as a half is 65520
+const F16_OVERFLOW: u32 = 0x477f_f000;
+// values below the smallest normal half, 2^-14, become subnormals
+const F16_MIN_NORMAL: u32 = (F32_EXP_BIAS - F16_EXP_BIAS + 1) << 23;
+// 0.5: adding it places the half subnormal mantissa in the low bits of the f32 mantissa
+const DENORM_MAGIC: u32 = (F32_EXP_BIAS - F16_EXP_BIAS + MANT_SHIFT + 1) << 23;
+const F16_INFINITY: u16 = 0x7c00;
+
+/// Convert one `f32` to the bits of a half precision value
+#[inline(always)]
+fn f32_to_f16(x: f32) -> u16 {
+    let bits = x.to_bits();
+    let sign = (bits >> 16) as u16 & 0x8000;
+    let abs = bits & 0x7fff_ffff;
+    // normal: rebias the exponent, and round the mantissa to nearest, ties to even
+    let odd = (abs >> MANT_SHIFT) & 1;
+    let rebiased = abs.wrapping_sub((F32_EXP_BIAS - F16_EXP_BIAS) << 23);
+    let normal = (rebiased.wrapping_add((1 << (MANT_SHIFT - 1)) - 1 + odd) >> MANT_SHIFT) as u16;
+    // subnormal: the float addition rounds the mantissa
+    let shifted = f32::from_bits(abs) + f32::from_bits(DENORM_MAGIC);
+    let subnormal = shifted.to_bits().wrapping_sub(DENORM_MAGIC) as u16;
+    let h = if abs < F16_MIN_NORMAL { subnormal } else { normal };
+    let h = if abs >= F16_OVERFLOW { F16_INFINITY } else { h };
+    h | sign
+}
+
+/// Convert the bits of one half precision value to `f32`
+#[inline(always)]
+fn f16_to_f32(h: u16) -> f32 {
+    let sign = ((h & 0x8000) as u32) << 16;
+    let shifted = ((h & 0x7fff) as u32) << MANT_SHIFT;
+    let exp_mask = (F16_INFINITY as u32) << MANT_SHIFT;
+    let exp = shifted & exp_mask;
+    let normal = shifted + ((F32_EXP_BIAS - F16_EXP_BIAS) << 23);
+    // infinity and NaN keep the maximal exponent
+    let special = normal + ((128 - 16) << 23);
+    // subnormal: the float subtraction normalizes the mantissa
+    let subnormal = (f32::from_bits(normal + (1 << 23)) - f32::from_bits(F16_MIN_NORMAL)).to_bits();
+    let bits = if exp == exp_mask { special } else if exp == 0 { subnormal } else { normal };
+    f32::from_bits(bits | sign)
+}
+
+/// Convert `f32` values to half precision bits.
+///
+/// ***Panics*** if the slices are not of equal length.
+pub fn from_f32(input: &[Fast<f32>], out: &mut [u16]) {
+    assert_eq!(input.len(), out.len(), "from_f32: slices must be of equal length");
+    for (o, x) in out.iter_mut().zip(input) {
+        *o = f32_to_f16(x.0);
+    }
+}
+
+/// Convert half precision bits to `f32` values.
+///
+/// ***Panics*** if the slices are not of equal length.
+///
+/// # Safety
+///
+/// The half precision values must be finite: infinite and NaN values are invalid in
+/// [`Fast`].
+pub unsafe fn to_f32(input: &[u16], out: &mut [Fast<f32>]) {
+    assert_eq!(input.len(), out.len(), "to_f32: slices must be of equal length");
+    for (o, &h) in out.iter_mut().zip(input) {
+        let x = f16_to_f32(h);
+        debug_assert!(x.is_finite(), "to_f32: half precision value {:#06x} is not finite", h);
+        *o = Fast(x);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn conversions() {
+        let tiny = f32::from_bits(0x3380_0000); // 2^-24, the smallest subnormal half
+        let cases = [
+            (0., 0x0000), (-0., 0x8000), (1., 0x3c00), (-2., 0xc000), (65504., 0x7bff),
+            (65519., 0x7bff), (65520., 0x7c00), (-1e30, 0xfc00), (tiny, 0x0001),
+            (tiny / 2., 0x0000), (tiny * 1.5, 0x0002), (tiny * 2.5, 0x0002),
+            (1. + 1. / 2048., 0x3c00), (1. + 3. / 2048., 0x3c02), (6.1e-5, 0x03ff),
+        ];
+        for (x, h) in cases {
+            let mut out = [0];
+            from_f32(&[Fast(x)], &mut out);
+            assert_eq!(out[0], h, "{}", x);
+        }
+        assert!(f16_to_f32(0x7c00).is_infinite() && f16_to_f32(0x7e00).is_nan());
+
+        // every finite half converts exactly and back
+        for h in 0..=u16::MAX {
+            if h & 0x7c00 == 0x7c00 {
+                continue;
+            }
+            let mut x = [Fast(0.)];
+            unsafe { to_f32(&[h], &mut x); }
+            let mut out = [0];
+            from_f32(&x, &mut out);
+            assert_eq!(out[0], h);
+        }
+        let mut x = [Fast(0.); 3];
+        unsafe { to_f32(&[0x0001, 0x0400, 0xc000], &mut x); }
+        assert_eq!(x, [Fast(tiny), Fast(6.1035156e-5), Fast(-2.)]);
+    }
+}
@@ -0,0 +1,134 @@
+//! Numerical integration of sampled data.
+//!
+//! The rules take the samples `ys` of a function, either at uniform spacing `dx` or at the
+//! points `xs`, and accumulate with the fast-math flags.
+//!
+//! ```
+//! use fast_floats::{integrate, Fast};
+//!
+//! let f = |x| unsafe { Fast::new(x) };
+//! // x² sampled at 0, 0.5, .., 2; Simpson's rule is exact for cubics
+//! let ys = [0., 0.25, 1., 2.25, 4.].map(f);
+//! assert_eq!(integrate::simpson(&ys, f(0.5)), f(8. / 3.));
+//! assert_eq!(integrate::trapezoid(&ys, f(0.5)), f(2.75));
+//! ```
+
+use crate::slice;
+use crate::{Fast, FastFloat};
+
+/// Integrate samples at uniform spacing `dx` with the trapezoidal rule.
+///
+/// Return zero for fewer than two samples.
+pub fn trapezoid<F: FastFloat>(ys: &[Fast<F>], dx: Fast<F>) -> Fast<F> {
+    let [first, inner @ .., last] = ys else { return Fast(F::ZERO) };
+    dx * ((*first + *last) * F::from_f64(0.5) + slice::sum(inner))
+}
+
+/// Integrate samples at the points `xs` with the trapezoidal rule.
+///
+/// Return zero for fewer than two samples.
+///
+/// ***Panics*** if the slices are not of equal length.
+pub fn trapezoid_x<F: FastFloat>(xs: &[Fast<F>], ys: &[Fast<F>]) -> Fast<F> {
+    assert_eq!(xs.len(), ys.len(), "trapezoid_x: slices must be of equal length");
+    let sum = xs.windows(2).zip(ys.windows(2)).fold(Fast(F::ZERO), |acc, (x, y)| {
+        (x[1] - x[0]) * (y[0] + y[1]) + acc
+    });
+    sum * F::from_f64(0.5)
+}
+
+/// Integrate samples at uniform spacing `dx` with composite Simpson's rule.
+///
+/// For an even number of samples (an odd number of intervals), the last three intervals use
+/// Simpson's 3/8 rule, so the result stays exact for polynomials up to degree three. For two
+/// samples, this is the trapezoidal rule, and for fewer it is zero.
+pub fn simpson<F: FastFloat>(ys: &[Fast<F>], dx: Fast<F>) -> Fast<F> {
+    let n = ys.len();
+    if n < 3 {
+        return trapezoid(ys, dx);
+    }
+    // the part with an even number of intervals, followed by three intervals if needed
+    let m = if n % 2 == 1 { n } else { n - 3 };
+    let mut sum = Fast(F::ZERO);
+    if m >= 3 {
+        let (odd, even) = ys[1..m - 1].iter().enumerate().fold(
+            (Fast(F::ZERO), Fast(F::ZERO)),
+            |(odd, even), (i, &y)| if i % 2 == 0 { (odd + y, even) } else { (odd, even + y) });
+        sum = (ys[0] + ys[m - 1] + odd * F::from_f64(4.) + even * F::from_f64(2.))
+            * F::from_f64(1. / 3.);
+    }
+    if m < n {
+        let t = &ys[n - 4..];
+        sum += (t[0] + t[3] + (t[1] + t[2]) * F::from_f64(3.)) * F::from_f64(3. / 8.);
+    }
+    sum * dx
+}
+
+/// Integrate samples at the points `xs` with composite Simpson's rule for non-uniform
+/// spacing.
+///
+/// The rule is exact for quadratics. For an even number of samples, the last interval is
+/// integrated using the quadratic through the last three points. Two samples use the
+/// trapezoidal rule, and fewer give zero. The points must be distinct.
+///
+/// ***Panics*** if the slices are not of equal length.
+pub fn simpson_x<F: FastFloat>(xs: &[Fast<F>], ys: &[Fast<F>]) -> Fast<F> {
+    assert_eq!(xs.len(), ys.len(), "simpson_x: slices must be of equal length");
+    let n = xs.len();
+    if n < 3 {
+        return trapezoid_x(xs, ys);
+    }
+    let (two, three, six) = (Fast(F::from_f64(2.)), Fast(F::from_f64(3.)), Fast(F::from_f64(6.)));
+    let m = if n % 2 == 1 { n } else { n - 1 };
+    let mut sum = Fast(F::ZERO);
+    for i in (0..m - 2).step_by(2) {
+        let (h0, h1) = (xs[i + 1] - xs[i], xs[i + 2] - xs[i + 1]);
+        let h = h0 + h1;
+        sum += h / six * ((-(h1 / h0) + two) * ys[i] + h * h / (h0 * h1) * ys[i + 1]
+                          + (-(h0 / h1) + two) * ys[i + 2]);
+    }
+    if m < n {
+        let (h0, h1) = (xs[n - 2] - xs[n - 3], xs[n - 1] - xs[n - 2]);
+        let h = h0 + h1;
+        let alpha = (two * h1 * h1 + three * h0 * h1) / (six * h);
+        let beta = (h1 * h1 + three * h0 * h1) / (six * h0);
+        let eta = h1 * h1 * h1 / (six * h0 * h);
+        sum += alpha * ys[n - 1] + beta * ys[n - 2] - eta * ys[n - 3];
+    }
+    sum
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cubic(x: f64) -> f64 { x * x * x - 2. * x + 1. }
+    // the integral of cubic from 0 to x
+    fn integral(x: f64) -> f64 { x * x * x * x / 4. - x * x + x }
+
+    fn close(a: Fast<f64>, b: f64) -> bool { (a.get() - b).abs() < 1e-12 }
+
+    #[test]
+    fn rules() {
+        let dx = 0.25;
+        let xs: [Fast<f64>; 9] = std::array::from_fn(|i| Fast(i as f64 * dx));
+        let ys = xs.map(|x| Fast(cubic(x.get())));
+        for n in 2..9 {
+            let end = (n - 1) as f64 * dx;
+            if n >= 3 {
+                assert!(close(simpson(&ys[..n], Fast(dx)), integral(end)), "{}", n);
+            }
+            assert!(close(trapezoid(&ys[..n], Fast(dx)), trapezoid_x(&xs[..n], &ys[..n]).get()));
+        }
+        assert_eq!(trapezoid(&[Fast(1.)], Fast(1.)), Fast(0.));
+        assert_eq!(simpson::<f32>(&[], Fast(1.)), Fast(0.));
+
+        // non-uniform points, x² - x
+        let xs = [0., 0.1, 0.5, 0.6, 1.2, 2.].map(Fast);
+        let ys = xs.map(|x| x * x - x);
+        let integral = |x: f64| x * x * x / 3. - x * x / 2.;
+        assert!(close(simpson_x(&xs, &ys), integral(2.)));
+        assert!(close(simpson_x(&xs[..5], &ys[..5]), integral(1.2)));
+        assert!(close(trapezoid_x(&xs[..2], &ys[..2]), 0.1 * ys[1].get() / 2.));
+    }
+}
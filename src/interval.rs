@@ -0,0 +1,247 @@
+//! Interval arithmetic, to bound the range of a result.
+//!
+//! An [`IntervalF`] holds a lower and an upper bound. Each operation rounds its lower bound
+//! down and its upper bound up, so the exact result of the computation on any values in the
+//! input intervals is in the output interval. The direction of each rounding is found with
+//! the error-free transformations in [`eft`](crate::eft), which keeps the bounds tight
+//! without changing the rounding mode.
+//!
+//! The bounds are plain floats, and may be infinite: an operation that overflows bounds its
+//! result by the largest finite value and infinity.
+//!
+//! ```
+//! use fast_floats::interval::IntervalF;
+//! use fast_floats::Fast;
+//!
+//! let f = |x| IntervalF::point(unsafe { Fast::new(x) });
+//! let mut sum = f(0.);
+//! for _ in 0..10 {
+//!     sum += f(0.1);
+//! }
+//! // 0.1 is not exact, so the sum of the rounded inputs is not exactly one either
+//! assert!(sum.lo() < 1. && sum.hi() > 0.99999 && sum.width() < 1e-15);
+//! ```
+
+use std::ops::{Add, AddAssign, Div, DivAssign, Mul, MulAssign, Neg, Sub, SubAssign};
+
+use crate::eft;
+use crate::{Fast, FastFloat};
+
+/// An interval of values, see the module documentation.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct IntervalF<F> {
+    lo: F,
+    hi: F,
+}
+
+impl<F: FastFloat> IntervalF<F> {
+    /// Create the interval from `lo` to `hi`, inclusive.
+    ///
+    /// ***Panics*** if `lo` is greater than `hi`.
+    pub fn new(lo: Fast<F>, hi: Fast<F>) -> Self {
+        assert!(lo <= hi, "IntervalF::new: lo must not be greater than hi");
+        IntervalF { lo: lo.0, hi: hi.0 }
+    }
+
+    /// Create the interval that contains only `x`
+    pub fn point(x: Fast<F>) -> Self { IntervalF { lo: x.0, hi: x.0 } }
+
+    /// Return the lower bound
+    pub fn lo(&self) -> F { self.lo }
+
+    /// Return the upper bound
+    pub fn hi(&self) -> F { self.hi }
+
+    /// Return the width of the interval, rounded up
+    pub fn width(&self) -> F {
+        let (d, e) = eft::two_sum(Fast(self.hi), Fast(-self.lo));
+        up(d.0, e.0)
+    }
+
+    /// Return the midpoint of the interval
+    pub fn midpoint(&self) -> F {
+        let half = F::from_f64(0.5);
+        let mid = F::add_strict(F::mul_strict(self.lo, half), F::mul_strict(self.hi, half));
+        // the interval of all values
+        if mid.is_nan() { F::ZERO } else { mid }
+    }
+
+    /// Return `true` if `x` is in the interval
+    pub fn contains(&self, x: Fast<F>) -> bool { self.lo <= x.0 && x.0 <= self.hi }
+
+    /// Return the square root.
+    ///
+    /// The negative part of the interval is ignored, so the square root of an interval of
+    /// negative values is zero.
+    pub fn sqrt(self) -> Self {
+        let sqrt = |x: F| {
+            let x = x.max(F::ZERO);
+            let q = x.sqrt_strict();
+            // sqrt(x) - q has the sign of x - q²
+            (q, (-q).mul_add(q, x))
+        };
+        let (lo, e) = sqrt(self.lo);
+        let (hi, f) = sqrt(self.hi);
+        IntervalF { lo: down(lo, e), hi: up(hi, f) }
+    }
+}
+
+impl<F: FastFloat> From<Fast<F>> for IntervalF<F> {
+    fn from(x: Fast<F>) -> Self { IntervalF::point(x) }
+}
+
+fn infinity<F: FastFloat>() -> F { F::MAX.next_up() }
+
+/// Round the rounded-to-nearest result `r` with error `exact - r` down to a lower bound
+#[inline]
+fn down<F: FastFloat>(r: F, error: F) -> F {
+    if r.is_nan() {
+        -infinity::<F>()
+    } else if error >= F::ZERO {
+        r
+    } else {
+        // the error is negative, or NaN on overflow
+        r.next_down()
+    }
+}
+
+/// Round the rounded-to-nearest result `r` with error `exact - r` up to an upper bound
+#[inline]
+fn up<F: FastFloat>(r: F, error: F) -> F {
+    if r.is_nan() {
+        infinity()
+    } else if error <= F::ZERO {
+        r
+    } else {
+        r.next_up()
+    }
+}
+
+/// Return the product `a * b` rounded down and up
+#[inline]
+fn mul_bounds<F: FastFloat>(a: F, b: F) -> (F, F) {
+    let (p, e) = eft::two_prod(Fast(a), Fast(b));
+    (down(p.0, e.0), up(p.0, e.0))
+}
+
+/// Return the quotient `a / b` rounded down and up
+#[inline]
+fn div_bounds<F: FastFloat>(a: F, b: F) -> (F, F) {
+    let q = F::div_strict(a, b);
+    // a / b - q has the sign of (a - q b) / b
+    let r = (-q).mul_add(b, a);
+    let e = if b > F::ZERO { r } else { -r };
+    (down(q, e), up(q, e))
+}
+
+/// Return the hull of the bounds of four products or quotients
+fn hull<F: FastFloat>(bounds: [(F, F); 4]) -> IntervalF<F> {
+    let (mut lo, mut hi) = bounds[0];
+    for &(l, h) in &bounds[1..] {
+        lo = lo.min(l);
+        hi = hi.max(h);
+    }
+    IntervalF { lo, hi }
+}
+
+impl<F: FastFloat> Add for IntervalF<F> {
+    type Output = Self;
+    fn add(self, rhs: Self) -> Self {
+        let (lo, e) = eft::two_sum(Fast(self.lo), Fast(rhs.lo));
+        let (hi, f) = eft::two_sum(Fast(self.hi), Fast(rhs.hi));
+        IntervalF { lo: down(lo.0, e.0), hi: up(hi.0, f.0) }
+    }
+}
+
+impl<F: FastFloat> Sub for IntervalF<F> {
+    type Output = Self;
+    fn sub(self, rhs: Self) -> Self { self + -rhs }
+}
+
+impl<F: FastFloat> Mul for IntervalF<F> {
+    type Output = Self;
+    fn mul(self, rhs: Self) -> Self {
+        hull([mul_bounds(self.lo, rhs.lo), mul_bounds(self.lo, rhs.hi),
+              mul_bounds(self.hi, rhs.lo), mul_bounds(self.hi, rhs.hi)])
+    }
+}
+
+impl<F: FastFloat> Div for IntervalF<F> {
+    type Output = Self;
+    /// Divide; if `rhs` contains zero, the result is the interval of all values
+    fn div(self, rhs: Self) -> Self {
+        if rhs.lo <= F::ZERO && F::ZERO <= rhs.hi {
+            return IntervalF { lo: -infinity::<F>(), hi: infinity() };
+        }
+        hull([div_bounds(self.lo, rhs.lo), div_bounds(self.lo, rhs.hi),
+              div_bounds(self.hi, rhs.lo), div_bounds(self.hi, rhs.hi)])
+    }
+}
+
+impl<F: FastFloat> Neg for IntervalF<F> {
+    type Output = Self;
+    fn neg(self) -> Self { IntervalF { lo: -self.hi, hi: -self.lo } }
+}
+
+macro_rules! impl_op {
+    ($($name:ident, $method:ident, $assign_name:ident, $assign:ident;)*) => {
+        $(
+        // IntervalF<F> + Fast<F>
+        impl<F: FastFloat> $name<Fast<F>> for IntervalF<F> {
+            type Output = Self;
+            fn $method(self, rhs: Fast<F>) -> Self { self.$method(IntervalF::point(rhs)) }
+        }
+
+        // Fast<F> + IntervalF<F>
+        impl<F: FastFloat> $name<IntervalF<F>> for Fast<F> {
+            type Output = IntervalF<F>;
+            fn $method(self, rhs: IntervalF<F>) -> IntervalF<F> {
+                IntervalF::point(self).$method(rhs)
+            }
+        }
+
+        impl<F, Rhs> $assign_name<Rhs> for IntervalF<F>
+            where Self: $name<Rhs, Output = Self> + Copy,
+        {
+            fn $assign(&mut self, rhs: Rhs) { *self = (*self).$method(rhs) }
+        }
+        )*
+    }
+}
+
+impl_op! {
+    Add, add, AddAssign, add_assign;
+    Sub, sub, SubAssign, sub_assign;
+    Mul, mul, MulAssign, mul_assign;
+    Div, div, DivAssign, div_assign;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bounds() {
+        let x = IntervalF::new(Fast(1f32), Fast(2.));
+        let y = IntervalF::new(Fast(-3f32), Fast(0.5));
+        // exact operations do not widen
+        assert_eq!(x + y, IntervalF::new(Fast(-2.), Fast(2.5)));
+        assert_eq!(x - y, IntervalF::new(Fast(0.5), Fast(5.)));
+        assert_eq!(x * y, IntervalF::new(Fast(-6.), Fast(1.)));
+        assert_eq!(y / Fast(2.), IntervalF::new(Fast(-1.5), Fast(0.25)));
+        assert_eq!((x / y).hi(), f32::INFINITY);
+        assert_eq!(x.sqrt().lo(), 1.);
+
+        // inexact operations round outwards
+        let third = Fast(1f32) / IntervalF::point(Fast(3.));
+        assert_eq!((third.lo(), third.hi()), ((1f32 / 3.).next_down(), 1. / 3.));
+        let sqrt = IntervalF::point(Fast(2f64)).sqrt();
+        assert!(sqrt.lo() < sqrt.hi() && sqrt.lo() * sqrt.lo() < 2. && sqrt.hi() * sqrt.hi() > 2.);
+
+        // overflow is bounded by the largest finite value
+        let big = IntervalF::point(Fast(f32::MAX)) * Fast(2.);
+        assert_eq!((big.lo(), big.hi()), (f32::MAX, f32::INFINITY));
+        assert!(x.contains(Fast(1.5)) && !x.contains(Fast(2.5)));
+        assert_eq!(x.midpoint(), 1.5);
+    }
+}
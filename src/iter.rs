@@ -0,0 +1,207 @@
+//! Iterators over `Fast` values.
+
+use std::iter::{FusedIterator, TrustedLen};
+use std::slice;
+
+use crate::{Fast, FastFloat};
+
+/// An iterator that yields the elements of a slice of `F` as `Fast<F>`.
+///
+/// ```
+/// use fast_floats::iter::FastIter;
+/// use fast_floats::Fast;
+///
+/// let xs = [1., 2., 3.];
+/// let sum = unsafe { FastIter::new(&xs) }.fold(unsafe { Fast::new(0.) }, |acc, x| acc + x);
+/// assert_eq!(sum.get(), 6.);
+/// ```
+#[derive(Clone, Debug)]
+pub struct FastIter<'a, F> {
+    iter: slice::Iter<'a, F>,
+}
+
+impl<'a, F> FastIter<'a, F> {
+    /// Create an iterator over the elements of `xs`
+    ///
+    /// # Safety
+    ///
+    /// Every element of `xs` must be valid in `Fast`, see [`Fast::new`].
+    pub unsafe fn new(xs: &'a [F]) -> Self {
+        FastIter { iter: xs.iter() }
+    }
+
+    /// Return the remaining elements
+    pub fn as_slice(&self) -> &'a [F] { self.iter.as_slice() }
+}
+
+impl<F: Copy> Iterator for FastIter<'_, F> {
+    type Item = Fast<F>;
+
+    #[inline]
+    fn next(&mut self) -> Option<Fast<F>> { self.iter.next().map(|&x| Fast(x)) }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) { self.iter.size_hint() }
+
+    #[inline]
+    fn nth(&mut self, n: usize) -> Option<Fast<F>> { self.iter.nth(n).map(|&x| Fast(x)) }
+
+    #[inline]
+    fn count(self) -> usize { self.iter.len() }
+
+    #[inline]
+    fn last(self) -> Option<Fast<F>> { self.iter.last().map(|&x| Fast(x)) }
+
+    #[inline]
+    fn fold<B, G>(self, init: B, mut g: G) -> B
+        where G: FnMut(B, Fast<F>) -> B,
+    {
+        self.iter.fold(init, |acc, &x| g(acc, Fast(x)))
+    }
+}
+
+impl<F: Copy> DoubleEndedIterator for FastIter<'_, F> {
+    #[inline]
+    fn next_back(&mut self) -> Option<Fast<F>> { self.iter.next_back().map(|&x| Fast(x)) }
+
+    #[inline]
+    fn nth_back(&mut self, n: usize) -> Option<Fast<F>> {
+        self.iter.nth_back(n).map(|&x| Fast(x))
+    }
+
+    #[inline]
+    fn rfold<B, G>(self, init: B, mut g: G) -> B
+        where G: FnMut(B, Fast<F>) -> B,
+    {
+        self.iter.rfold(init, |acc, &x| g(acc, Fast(x)))
+    }
+}
+
+impl<F: Copy> ExactSizeIterator for FastIter<'_, F> {
+    #[inline]
+    fn len(&self) -> usize { self.iter.len() }
+}
+
+impl<F: Copy> FusedIterator for FastIter<'_, F> {}
+
+// Safety: the length is that of the slice iterator
+unsafe impl<F: Copy> TrustedLen for FastIter<'_, F> {}
+
+/// Return an iterator over the sums of the consecutive chunks of `N` elements of `xs`.
+///
+/// The last chunk is shorter if `N` does not divide the length. Each chunk is summed with
+/// [`slice::sum`](crate::slice::sum).
+///
+/// ***Panics*** if `N` is zero.
+///
+/// ```
+/// use fast_floats::iter::chunk_sums;
+/// use fast_floats::Fast;
+///
+/// let xs = [1., 2., 3., 4., 5.].map(|x| unsafe { Fast::new(x) });
+/// let sums: Vec<_> = chunk_sums::<2, _>(&xs).map(Fast::get).collect();
+/// assert_eq!(sums, [3., 7., 5.]);
+/// ```
+pub fn chunk_sums<const N: usize, F: FastFloat>(xs: &[Fast<F>]) -> ChunkSums<'_, F> {
+    ChunkSums { chunks: xs.chunks(N) }
+}
+
+/// An iterator over the sums of the chunks of a slice, see [`chunk_sums`].
+#[derive(Clone, Debug)]
+pub struct ChunkSums<'a, F> {
+    chunks: slice::Chunks<'a, Fast<F>>,
+}
+
+impl<F: FastFloat> Iterator for ChunkSums<'_, F> {
+    type Item = Fast<F>;
+
+    #[inline]
+    fn next(&mut self) -> Option<Fast<F>> { self.chunks.next().map(crate::slice::sum) }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) { self.chunks.size_hint() }
+
+    #[inline]
+    fn nth(&mut self, n: usize) -> Option<Fast<F>> { self.chunks.nth(n).map(crate::slice::sum) }
+
+    #[inline]
+    fn count(self) -> usize { self.chunks.len() }
+
+    #[inline]
+    fn last(self) -> Option<Fast<F>> { self.chunks.last().map(crate::slice::sum) }
+}
+
+impl<F: FastFloat> DoubleEndedIterator for ChunkSums<'_, F> {
+    #[inline]
+    fn next_back(&mut self) -> Option<Fast<F>> { self.chunks.next_back().map(crate::slice::sum) }
+}
+
+impl<F: FastFloat> ExactSizeIterator for ChunkSums<'_, F> {
+    #[inline]
+    fn len(&self) -> usize { self.chunks.len() }
+}
+
+impl<F: FastFloat> FusedIterator for ChunkSums<'_, F> {}
+
+// Safety: the length is that of the chunks iterator
+unsafe impl<F: FastFloat> TrustedLen for ChunkSums<'_, F> {}
+
+/// Return the dot product of the values from `xs` and `ys`.
+///
+/// This is for values that do not come from contiguous slices, for example strided views
+/// or generators; for slices [`slice::dot`](crate::slice::dot) is faster. Like
+/// [`Iterator::zip`], it stops at the end of the shorter iterator.
+///
+/// ```
+/// use fast_floats::iter::dot_iter;
+/// use fast_floats::Fast;
+///
+/// let rows = [[1., 2.], [3., 4.]].map(|r| r.map(|x| unsafe { Fast::new(x) }));
+/// // the dot product of the first column with itself
+/// let col = || rows.iter().map(|r| r[0]);
+/// assert_eq!(dot_iter(col(), col()).get(), 10.);
+/// ```
+pub fn dot_iter<F, I, J>(xs: I, ys: J) -> Fast<F>
+    where F: FastFloat,
+          I: IntoIterator<Item = Fast<F>>,
+          J: IntoIterator<Item = Fast<F>>,
+{
+    xs.into_iter().zip(ys).fold(Fast(F::ZERO), |acc, (x, y)| x * y + acc)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn iterate() {
+        let xs = [1f32, 2., 3., 4.];
+        let mut iter = unsafe { FastIter::new(&xs) };
+        assert_eq!(iter.len(), 4);
+        assert_eq!(iter.next(), Some(Fast(1.)));
+        assert_eq!(iter.next_back(), Some(Fast(4.)));
+        assert_eq!(iter.as_slice(), &[2., 3.]);
+        assert_eq!(iter.clone().rev().nth(1), Some(Fast(2.)));
+        assert_eq!(iter.fold(Fast(0.), |acc, x| acc + x), Fast(5.));
+    }
+
+    #[test]
+    fn chunks() {
+        let xs: [Fast<f64>; 10] = std::array::from_fn(|i| Fast(i as f64));
+        let mut sums = chunk_sums::<4, _>(&xs);
+        assert_eq!(sums.len(), 3);
+        assert_eq!(sums.next_back(), Some(Fast(17.)));
+        assert_eq!(sums.next(), Some(Fast(6.)));
+        assert_eq!(sums.next(), Some(Fast(22.)));
+        assert_eq!(sums.next(), None);
+        assert_eq!(chunk_sums::<1, f64>(&[]).count(), 0);
+    }
+
+    #[test]
+    fn dot() {
+        let xs = [1., 2., 3., 4., 5.];
+        let evens = unsafe { FastIter::new(&xs) }.step_by(2);
+        assert_eq!(dot_iter(evens, (0..).map(|i| Fast(i as f64))), Fast(13.));
+        assert_eq!(dot_iter(unsafe { FastIter::new(&xs) }, []), Fast(0.));
+    }
+}
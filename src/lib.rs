@@ -10,28 +10,118 @@
 //!
 //! [1]: https://llvm.org/docs/LangRef.html#fast-math-flags
 //!
+//! # Crate Features
+//!
+//! - `algebraic`: the operators use the “algebraic” intrinsics instead of the “fast” ones.
+//!   They allow the same reassociation and contraction, but not the assumption that
+//!   values are finite, so the operators are not undefined behavior for infinite and NaN
+//!   values. The rest of the API still requires values in `Fast` to be finite.
+//! - `alloc`: enables [`AlignedBuffer`], an owned buffer with SIMD-friendly alignment,
+//!   and `FftPlan`, precomputed FFT twiddle factors.
+//! - `defmt`: implements `defmt::Format` for [`Fast`], for logging on embedded targets.
+//! - `libm`: enables the inverse trigonometric and the hyperbolic methods on [`Fast`], such
+//!   as [`atan2`](Fast::atan2) and [`tanh`](Fast::tanh), using the `libm` crate.
+//! - `matrixmultiply`: enables the [`gemm`] module, matrix multiplication using the
+//!   `matrixmultiply` crate.
+//! - `micromath`: the `f32` methods [`sqrt`](Fast::sqrt), [`ln`](Fast::ln),
+//!   [`sin`](Fast::sin), [`cos`](Fast::cos) and [`powf`](Fast::powf), and the methods that
+//!   use them, compute with the approximations of the `micromath` crate instead of the
+//!   intrinsics. They are cheaper on targets without hardware support or a fast math
+//!   library, such as Cortex-M, but much less accurate: the square root is off by up to
+//!   about 6%. Features are unified across the build, so this changes the results for every
+//!   crate that uses `Fast<f32>` as soon as one crate enables it. The algorithms of this
+//!   crate that need an accurate square root, sine or cosine, such as
+//!   [`linalg::cholesky`] and [`dsp::Goertzel`], do not use the approximations.
+//! - `num-traits`: implements traits from the `num-traits` crate for [`Fast`], such as
+//!   `Euclid`.
+//! - `rayon`: enables the `par` module, parallel slice algorithms using the `rayon` crate.
+//!   Implies `alloc`.
+//! - `wide`: enables the `wide` module, conversions between arrays of fast values and the
+//!   SIMD vectors of the `wide` crate.
+//!
 //! # Rust Version
 //!
 //! This crate is nightly only and experimental. Breaking changes can occur at
 //! any time, if changes in Rust require it.
 #![no_std]
 #![feature(core_intrinsics)]
+#![feature(trusted_len)]
+#![allow(internal_features)]
+// the `Into<f32>` and `Into<f64>` impls are kept as they are, see `Fast::get`
+#![allow(clippy::from_over_into, clippy::needless_arbitrary_self_type)]
 
 extern crate core as std;
+#[cfg(feature = "alloc")]
+extern crate alloc;
 
-use std::intrinsics::{fadd_fast, fsub_fast, fmul_fast, fdiv_fast, frem_fast};
 use std::ops::{
     Add,
     Sub,
     Mul,
     Div,
     Rem,
+    Neg,
     AddAssign,
     SubAssign,
     MulAssign,
     DivAssign,
     RemAssign,
 };
+use std::cmp::Ordering;
+use std::num::FpCategory;
+
+mod float;
+mod log;
+mod matrix;
+pub mod accum;
+pub mod approx;
+pub mod arch;
+pub mod array;
+pub mod audit;
+pub mod bench;
+pub mod color;
+pub mod complex;
+pub mod diff;
+pub mod dsp;
+pub mod eft;
+pub mod fft;
+#[cfg(any(target_arch = "x86_64", all(target_arch = "x86", target_feature = "sse"),
+          target_arch = "aarch64"))]
+pub mod fpenv;
+pub mod fused;
+pub mod half;
+pub mod integrate;
+pub mod interval;
+pub mod iter;
+pub mod linalg;
+pub mod ml;
+pub mod ode;
+pub mod pcm;
+pub mod points;
+pub mod prelude;
+pub mod raw;
+pub mod roots;
+#[cfg(feature = "matrixmultiply")]
+pub mod gemm;
+#[cfg(feature = "rayon")]
+pub mod par;
+pub mod slice;
+pub mod sparse;
+pub mod stochastic;
+pub mod strided;
+pub mod table;
+#[cfg(feature = "wide")]
+pub mod wide;
+pub mod window;
+
+pub use crate::float::FastFloat;
+pub use crate::log::LogBase;
+pub use crate::matrix::FastMatrix;
+
+#[cfg(feature = "alloc")]
+mod aligned;
+#[cfg(feature = "alloc")]
+pub use crate::aligned::AlignedBuffer;
 
 /// “fast-math” wrapper for f32 and f64.
 ///
@@ -61,6 +151,388 @@ impl<F> Fast<F> {
 
     /// Get the inner value
     pub fn get(self) -> F { self.0 }
+
+    /// Apply `f` to the inner value and wrap the result
+    ///
+    /// # Safety
+    ///
+    /// The result of `f` must be valid in `Fast`, see [`Fast::new`].
+    #[inline(always)]
+    pub unsafe fn map(self, f: impl FnOnce(F) -> F) -> Self { Fast(f(self.0)) }
+
+    /// Apply `f` to the inner values of `self` and `other` and wrap the result
+    ///
+    /// # Safety
+    ///
+    /// The result of `f` must be valid in `Fast`, see [`Fast::new`].
+    #[inline(always)]
+    pub unsafe fn zip_with(self, other: Self, f: impl FnOnce(F, F) -> F) -> Self {
+        Fast(f(self.0, other.0))
+    }
+}
+
+impl<F: FastFloat> Fast<F> {
+    /// Return the square root
+    ///
+    /// The square root of a negative value is NaN, which is not valid in `Fast`.
+    #[inline(always)]
+    pub fn sqrt(self) -> Self { Fast(self.0.sqrt()) }
+
+    /// Return `self * a + b` computed with a fused multiply-add, rounding only once.
+    ///
+    /// The regular operators already allow the compiler to contract a multiply and an add,
+    /// but do not guarantee it; this does. On targets without hardware FMA it is a slow
+    /// library call. See also the [`fused`] module.
+    #[inline(always)]
+    pub fn mul_add(self, a: Self, b: Self) -> Self { Fast(self.0.mul_add(a.0, b.0)) }
+
+    /// Compute `self = self * a + b` with a fused multiply-add, see [`mul_add`](Fast::mul_add).
+    #[inline(always)]
+    pub fn mul_add_assign(&mut self, a: Self, b: Self) { *self = self.mul_add(a, b); }
+
+    /// Return `self` raised to the power `n`
+    ///
+    /// This calls the platform's `pow`. A negative value raised to a non-integer power is NaN,
+    /// and a result that overflows is infinite; neither is valid in `Fast`. See also
+    /// [`approx::powf`](crate::approx::powf).
+    #[inline(always)]
+    pub fn powf(self, n: Self) -> Self { Fast(self.0.powf(n.0)) }
+
+    /// Return the absolute value
+    #[inline(always)]
+    pub fn abs(self) -> Self { Fast(self.0.abs()) }
+
+    /// Return the minimum of the two values
+    #[inline(always)]
+    pub fn min(self, other: Self) -> Self { Fast(self.0.min(other.0)) }
+
+    /// Return the maximum of the two values
+    #[inline(always)]
+    pub fn max(self, other: Self) -> Self { Fast(self.0.max(other.0)) }
+
+    /// Restrict the value to the interval `min..=max`.
+    ///
+    /// This is computed as `self.max(min).min(max)`, which compiles to min/max instructions
+    /// without branches. `min` must not be greater than `max` (this is checked only in debug
+    /// builds).
+    #[inline(always)]
+    pub fn clamp(self, min: Self, max: Self) -> Self {
+        debug_assert!(min <= max, "clamp: min must not be greater than max");
+        self.max(min).min(max)
+    }
+
+    /// Return the quotient of Euclidean division, `self.rem_euclid(rhs)` is
+    /// `self - rhs * self.div_euclid(rhs)`.
+    ///
+    /// The quotient is `self / rhs` rounded toward negative infinity if `rhs > 0`, and
+    /// toward positive infinity if `rhs < 0`. Like `/`, the result is not valid in `Fast` if
+    /// `rhs` is zero.
+    #[inline]
+    pub fn div_euclid(self, rhs: Self) -> Self {
+        let q = self / rhs;
+        let q = Fast(q.0.abs().floor().copysign(q.0));
+        if self % rhs < Fast(F::ZERO) {
+            if rhs > Fast(F::ZERO) { q - F::ONE } else { q + F::ONE }
+        } else {
+            q
+        }
+    }
+
+    /// Return the least nonnegative remainder of `self` divided by `rhs`, in
+    /// `0 <= r < |rhs|` (up to rounding).
+    ///
+    /// This wraps values into a range, for example an angle into `0..2π` with
+    /// `x.rem_euclid(TAU)`. Like `%`, the result is not valid in `Fast` if `rhs` is zero.
+    ///
+    /// ```
+    /// use fast_floats::Fast;
+    ///
+    /// let f = |x| unsafe { Fast::new(x) };
+    /// assert_eq!(f(-1.).rem_euclid(f(4.)), f(3.));
+    /// assert_eq!(f(-7.).div_euclid(f(4.)), f(-2.));
+    /// ```
+    #[inline]
+    pub fn rem_euclid(self, rhs: Self) -> Self {
+        let r = self % rhs;
+        if r < Fast(F::ZERO) { r + rhs.abs() } else { r }
+    }
+
+    /// Return the midpoint `(self + other) / 2`, without intermediate overflow.
+    ///
+    /// Like `midpoint` on the primitive floats, this is the correctly rounded midpoint: the
+    /// sum is scaled down first only when it could overflow, and the halving is exact except
+    /// for the smallest values.
+    ///
+    /// ```
+    /// use fast_floats::Fast;
+    ///
+    /// let f = |x| unsafe { Fast::new(x) };
+    /// assert_eq!(f(1.).midpoint(f(4.)), f(2.5));
+    /// assert_eq!(f(f64::MAX).midpoint(f(f64::MAX)), f(f64::MAX));
+    /// ```
+    #[inline]
+    pub fn midpoint(self, other: Self) -> Self {
+        let half = F::from_f64(0.5);
+        let lo = Fast(F::MIN_POSITIVE) * F::from_f64(2.);
+        let hi = Fast(F::MAX) * half;
+        let (a, b) = (self.abs(), other.abs());
+        if a <= hi && b <= hi {
+            (self + other) * half
+        } else if a < lo {
+            self + other * half
+        } else if b < lo {
+            self * half + other
+        } else {
+            self * half + other * half
+        }
+    }
+
+    /// Return `if_true` if `mask` is true, and otherwise `if_false`, without a branch.
+    ///
+    /// The choice is made with bit operations on the values, so it compiles to a
+    /// conditional move or a vector blend. In a loop, this keeps a condition from preventing
+    /// vectorization.
+    ///
+    /// ```
+    /// use fast_floats::Fast;
+    ///
+    /// let f = |x| unsafe { Fast::new(x) };
+    /// let mut xs = [-2., 3., -1.].map(f);
+    /// for x in &mut xs {
+    ///     *x = Fast::select(*x < f(0.), -*x, *x);
+    /// }
+    /// assert_eq!(xs, [2., 3., 1.].map(f));
+    /// ```
+    #[inline(always)]
+    pub fn select(mask: bool, if_true: Self, if_false: Self) -> Self {
+        Fast(F::select(mask, if_true.0, if_false.0))
+    }
+
+    /// Return `true` if the value is NaN
+    #[inline(always)]
+    pub fn is_nan(self) -> bool { self.0.is_nan() }
+
+    /// Return `true` if the value is positive or negative infinity
+    #[inline(always)]
+    pub fn is_infinite(self) -> bool { self.0.is_infinite() }
+
+    /// Return `true` if the value is neither infinite nor NaN
+    #[inline(always)]
+    pub fn is_finite(self) -> bool { self.0.is_finite() }
+
+    /// Return `true` if the value is neither zero, infinite, subnormal nor NaN
+    #[inline(always)]
+    pub fn is_normal(self) -> bool { self.0.is_normal() }
+
+    /// Return `true` if the sign bit is clear
+    #[inline(always)]
+    pub fn is_sign_positive(self) -> bool { self.0.is_sign_positive() }
+
+    /// Return `true` if the sign bit is set
+    #[inline(always)]
+    pub fn is_sign_negative(self) -> bool { self.0.is_sign_negative() }
+
+    /// Return the floating point category of the value
+    #[inline(always)]
+    pub fn classify(self) -> FpCategory { self.0.classify() }
+
+    /// Return the bit pattern of the value, see [`f64::to_bits`].
+    ///
+    /// The bits are also printed by the `LowerHex` and `UpperHex` implementations of `Fast`,
+    /// which is useful when comparing results that differ in the last bits.
+    ///
+    /// ```
+    /// use fast_floats::Fast;
+    ///
+    /// let x = unsafe { Fast::new(1.5f32) };
+    /// assert_eq!(x.to_bits(), 0x3fc0_0000);
+    /// assert_eq!(format!("{:#x}", x), "0x3fc00000");
+    /// ```
+    #[inline(always)]
+    pub fn to_bits(self) -> F::Bits { self.0.to_bits() }
+
+    /// Create a value from its bit pattern, see [`f64::from_bits`].
+    ///
+    /// # Safety
+    ///
+    /// The bits must be those of a value that is valid in `Fast`, see [`Fast::new`].
+    #[inline(always)]
+    pub unsafe fn from_bits(bits: F::Bits) -> Self { Fast(F::from_bits(bits)) }
+
+    /// Return the next representable value greater than `self`.
+    ///
+    /// For the largest finite value this is infinity, which is not valid in `Fast`.
+    #[inline(always)]
+    pub fn next_up(self) -> Self { Fast(self.0.next_up()) }
+
+    /// Return the next representable value less than `self`.
+    ///
+    /// For the most negative finite value this is negative infinity, which is not valid in
+    /// `Fast`.
+    #[inline(always)]
+    pub fn next_down(self) -> Self { Fast(self.0.next_down()) }
+
+    /// Return the distance between `self` and `other` in units in the last place (ULPs),
+    /// the number of representable values between them, plus one.
+    ///
+    /// Positive and negative zero are at distance zero. This is the meaningful way to measure
+    /// how much the result of a fast computation has drifted from a reference.
+    ///
+    /// ```
+    /// use fast_floats::Fast;
+    ///
+    /// let x = unsafe { Fast::new(1.0f32) };
+    /// assert_eq!(x.ulps_between(x.next_up().next_up()), 2);
+    /// ```
+    #[inline]
+    pub fn ulps_between(self, other: Self) -> u64 {
+        self.0.ordered_bits().abs_diff(other.0.ordered_bits())
+    }
+
+    /// Return the ordering between `self` and `other` according to IEEE 754 `totalOrder`.
+    ///
+    /// This is a total order, unlike the `PartialOrd` implementation. For the finite values
+    /// that are valid in `Fast` it agrees with `PartialOrd`, except that `-0` is less than `+0`.
+    #[inline(always)]
+    pub fn total_cmp(&self, other: &Self) -> Ordering { self.0.total_cmp(&other.0) }
+
+    /// Return `true` if `self` and `other` are approximately equal: if their difference is at
+    /// most `abs_tol`, or at most `rel_tol` times the larger of their magnitudes.
+    ///
+    /// ```
+    /// use fast_floats::Fast;
+    ///
+    /// let f = |x| unsafe { Fast::new(x) };
+    /// assert!(f(100.).approx_eq(f(100.1), f(1e-2), f(0.)));
+    /// assert!(f(1e-9).approx_eq(f(0.), f(0.), f(1e-6)));
+    /// ```
+    #[inline]
+    pub fn approx_eq(self, other: Self, rel_tol: Self, abs_tol: Self) -> bool {
+        // strict, since the difference overflows for values of opposite signs near MAX
+        let diff = F::sub_strict(self.0, other.0).abs();
+        let rel = F::mul_strict(rel_tol.0, self.0.abs().max(other.0.abs()));
+        diff <= abs_tol.0.max(rel)
+    }
+
+    /// Convert radians to degrees
+    #[inline(always)]
+    pub fn to_degrees(self) -> Self {
+        self * F::from_f64(180. / std::f64::consts::PI)
+    }
+
+    /// Convert degrees to radians
+    #[inline(always)]
+    pub fn to_radians(self) -> Self {
+        self * F::from_f64(std::f64::consts::PI / 180.)
+    }
+
+    /// Return the natural logarithm of `self`
+    ///
+    /// This calls the platform's `log`. The logarithm of zero is infinite and of a negative
+    /// value is NaN; neither is valid in `Fast`. See also [`approx::ln`](crate::approx::ln).
+    #[inline(always)]
+    pub fn ln(self) -> Self { Fast(self.0.ln()) }
+
+    /// Return the logarithm of `self` in base `base`, computed as `ln self / ln base`
+    ///
+    /// Use [`LogBase`] to compute the reciprocal of `ln base` only once for many values.
+    #[inline]
+    pub fn log(self, base: Self) -> Self { LogBase::new(base).log(self) }
+
+    /// Return the sine of `self` (in radians)
+    ///
+    /// This calls the platform's `sin`; see also [`approx::sin`](crate::approx::sin).
+    #[inline(always)]
+    pub fn sin(self) -> Self { Fast(self.0.sin()) }
+
+    /// Return the cosine of `self` (in radians)
+    ///
+    /// This calls the platform's `cos`; see also [`approx::cos`](crate::approx::cos).
+    #[inline(always)]
+    pub fn cos(self) -> Self { Fast(self.0.cos()) }
+
+    /// Return the tangent of `self` (in radians), computed as `sin / cos`
+    #[inline(always)]
+    pub fn tan(self) -> Self {
+        let (sin, cos) = self.sin_cos();
+        sin / cos
+    }
+
+    /// Return the sine and cosine of `self` (in radians), `(sin, cos)`
+    ///
+    /// The compiler can combine the two into one call to `sincos` where it is available.
+    #[inline(always)]
+    pub fn sin_cos(self) -> (Self, Self) { (self.sin(), self.cos()) }
+
+    /// Return the length of the hypotenuse, `√(self² + other²)`, using the naive formula.
+    ///
+    /// This is fast, but the intermediate squares overflow if either value is larger than
+    /// the square root of the largest float (about 1.8e19 for `f32`), and precision is lost
+    /// if they are smaller than the smallest normal float; see [`hypot_robust`](Fast::hypot_robust).
+    #[inline(always)]
+    pub fn hypot(self, other: Self) -> Self {
+        (self * self + other * other).sqrt()
+    }
+
+    /// Return the length of the hypotenuse, `√(self² + other²)`, scaled to avoid
+    /// intermediate overflow and underflow.
+    ///
+    /// This costs a division and a few comparisons more than [`hypot`](Fast::hypot).
+    #[inline]
+    pub fn hypot_robust(self, other: Self) -> Self {
+        let (a, b) = (self.abs(), other.abs());
+        let (big, small) = if a >= b { (a, b) } else { (b, a) };
+        if big == Fast(F::ZERO) {
+            return big;
+        }
+        let ratio = small / big;
+        big * (ratio * ratio + F::ONE).sqrt()
+    }
+}
+
+/// Methods that use the `libm` crate.
+#[cfg(feature = "libm")]
+impl<F: FastFloat> Fast<F> {
+    /// Return the arctangent of `self`, in radians in the range `-π/2..=π/2`
+    #[inline]
+    pub fn atan(self) -> Self { Fast(self.0.atan()) }
+
+    /// Return the four quadrant arctangent of `self` (y) and `x`, in radians in the range
+    /// `-π..=π`
+    #[inline]
+    pub fn atan2(self, x: Self) -> Self { Fast(self.0.atan2(x.0)) }
+
+    /// Return the arcsine of `self`, in radians in the range `-π/2..=π/2`
+    ///
+    /// The arcsine of a value outside `-1..=1` is NaN, which is not valid in `Fast`.
+    #[inline]
+    pub fn asin(self) -> Self { Fast(self.0.asin()) }
+
+    /// Return the arccosine of `self`, in radians in the range `0..=π`
+    ///
+    /// The arccosine of a value outside `-1..=1` is NaN, which is not valid in `Fast`.
+    #[inline]
+    pub fn acos(self) -> Self { Fast(self.0.acos()) }
+
+    /// Return the hyperbolic sine of `self`
+    ///
+    /// The result overflows to infinity, which is not valid in `Fast`, for `|self|` larger
+    /// than about 89 (`f32`) or 710 (`f64`).
+    #[inline]
+    pub fn sinh(self) -> Self { Fast(self.0.sinh()) }
+
+    /// Return the hyperbolic cosine of `self`
+    ///
+    /// The result overflows to infinity, which is not valid in `Fast`, for `|self|` larger
+    /// than about 89 (`f32`) or 710 (`f64`).
+    #[inline]
+    pub fn cosh(self) -> Self { Fast(self.0.cosh()) }
+
+    /// Return the hyperbolic tangent of `self`
+    ///
+    /// See also [`approx::tanh`](crate::approx::tanh), which is faster and vectorizes.
+    #[inline]
+    pub fn tanh(self) -> Self { Fast(self.0.tanh()) }
 }
 
 impl Into<f32> for Fast<f32> {
@@ -75,22 +547,12 @@ macro_rules! impl_op {
     ($($name:ident, $method:ident, $intrins:ident;)*) => {
         $(
         // Fast<F> + F
-        impl $name<f64> for Fast<f64> {
-            type Output = Self;
-            #[inline(always)]
-            fn $method(self, rhs: f64) -> Self::Output {
-                unsafe {
-                    Fast($intrins(self.0, rhs))
-                }
-            }
-        }
-
-        impl $name<f32> for Fast<f32> {
+        impl<F: FastFloat> $name<F> for Fast<F> {
             type Output = Self;
             #[inline(always)]
-            fn $method(self, rhs: f32) -> Self::Output {
+            fn $method(self, rhs: F) -> Self::Output {
                 unsafe {
-                    Fast($intrins(self.0, rhs))
+                    Fast(F::$intrins(self.0, rhs))
                 }
             }
         }
@@ -113,15 +575,7 @@ macro_rules! impl_op {
         }
 
         // Fast<F> + Fast<F>
-        impl $name for Fast<f64> {
-            type Output = Self;
-            #[inline(always)]
-            fn $method(self, rhs: Self) -> Self::Output {
-                self.$method(rhs.0)
-            }
-        }
-
-        impl $name for Fast<f32> {
+        impl<F: FastFloat> $name for Fast<F> {
             type Output = Self;
             #[inline(always)]
             fn $method(self, rhs: Self) -> Self::Output {
@@ -165,6 +619,18 @@ impl_assignop! {
     RemAssign, rem_assign, Rem, rem;
 }
 
+impl<F: FastFloat> Neg for Fast<F> {
+    type Output = Self;
+    #[inline(always)]
+    fn neg(self) -> Self { Fast(-self.0) }
+}
+
+#[cfg(feature = "num-traits")]
+impl<F: FastFloat> num_traits::Euclid for Fast<F> {
+    fn div_euclid(&self, v: &Self) -> Self { Fast::div_euclid(*self, *v) }
+    fn rem_euclid(&self, v: &Self) -> Self { Fast::rem_euclid(*self, *v) }
+}
+
 use std::fmt;
 macro_rules! impl_format {
     ($($name:ident)+) => {
@@ -180,11 +646,39 @@ macro_rules! impl_format {
 
 impl_format!(Debug Display LowerExp UpperExp);
 
+/// Format the bit pattern of the value, see [`Fast::to_bits`]
+impl<F: FastFloat> fmt::LowerHex for Fast<F> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::LowerHex::fmt(&self.to_bits(), f)
+    }
+}
+
+/// Format the bit pattern of the value, see [`Fast::to_bits`]
+impl<F: FastFloat> fmt::UpperHex for Fast<F> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::UpperHex::fmt(&self.to_bits(), f)
+    }
+}
+
+#[cfg(feature = "defmt")]
+impl<F: defmt::Format> defmt::Format for Fast<F> {
+    fn format(&self, f: defmt::Formatter) { self.0.format(f) }
+}
+
 
 #[cfg(test)]
 mod tests {
+    extern crate std as libstd;
+
     use super::*;
 
+    /// Assert that the result of the `f32` math functions is `expected`, or within 10% of it
+    /// with the approximations of the `micromath` feature.
+    pub(crate) fn assert_f32_math(x: Fast<f32>, expected: f32) {
+        let tol = if cfg!(feature = "micromath") { 0.1 * expected.abs() } else { 0. };
+        assert!((x.get() - expected).abs() <= tol, "{:?} != {}", x, expected);
+    }
+
     macro_rules! test_op {
         ($($op:tt)+) => {
             $(
@@ -196,6 +690,7 @@ mod tests {
     #[test]
     fn each_op() {
         test_op!(+ - * / %);
+        assert_eq!(-Fast(2.), Fast(-2.));
     }
 
     macro_rules! assign_op {
@@ -208,6 +703,150 @@ mod tests {
         }
     }
 
+    #[test]
+    fn hypot() {
+        assert_eq!(Fast(3.).hypot(Fast(-4.)), Fast(5.));
+        assert_f32_math(Fast(3e30f32).hypot_robust(Fast(4e30)), 5e30);
+        assert_eq!(Fast(0.).hypot_robust(Fast(0.)), Fast(0.));
+    }
+
+    #[test]
+    fn mul_add() {
+        // rounding only once is observable: 1 + 2^-30 squared, minus its rounded value
+        let x = Fast(1. + 2f64.powi(-30));
+        let prod = x * x;
+        assert_eq!(x.mul_add(x, -prod), Fast(2f64.powi(-60)));
+        let mut y = Fast(2f32);
+        y.mul_add_assign(Fast(3.), Fast(1.));
+        assert_eq!(y, Fast(7.));
+    }
+
+    #[test]
+    fn powf() {
+        assert_eq!(Fast(9.).powf(Fast(0.5)), Fast(3.));
+        assert_f32_math(Fast(2f32).powf(Fast(-2.)), 0.25);
+    }
+
+    #[test]
+    fn logarithms() {
+        assert_eq!(Fast(1f32).ln(), Fast(0.));
+        assert!((Fast(1000.).log(Fast(10.)) - 3.).abs() < Fast(1e-15));
+    }
+
+    #[test]
+    fn trig() {
+        let x = Fast(0.5f64);
+        assert_eq!(x.sin_cos(), (Fast(0.5f64.sin()), Fast(0.5f64.cos())));
+        assert!((x.tan().get() - 0.5f64.tan()).abs() < 1e-15);
+        assert_eq!(Fast(0f32).sin(), Fast(0.));
+    }
+
+    #[cfg(feature = "libm")]
+    #[test]
+    fn inverse_trig() {
+        use std::f64::consts::{FRAC_PI_2, FRAC_PI_4, PI};
+        assert_eq!(Fast(1.).atan(), Fast(FRAC_PI_4));
+        assert_eq!(Fast(0.).atan2(Fast(-1.)), Fast(PI));
+        assert_eq!(Fast(1.).asin(), Fast(FRAC_PI_2));
+        assert!(Fast(-1f32).acos().ulps_between(Fast(std::f32::consts::PI)) <= 1);
+    }
+
+    #[cfg(feature = "libm")]
+    #[test]
+    fn hyperbolic() {
+        let x = Fast(0.75f64);
+        assert!((x.cosh() * x.cosh() - x.sinh() * x.sinh() - 1.).abs() < Fast(1e-15));
+        assert!((x.tanh() - x.sinh() / x.cosh()).abs() < Fast(1e-15));
+        assert_eq!(Fast(-30f32).tanh(), Fast(-1.));
+    }
+
+    #[test]
+    fn min_max() {
+        assert_eq!(Fast(1.).min(Fast(2.)), Fast(1.));
+        assert_eq!(Fast(1.).max(Fast(2.)), Fast(2.));
+        assert_eq!(Fast(3.).clamp(Fast(-1.), Fast(1.)), Fast(1.));
+        assert_eq!(Fast(-3.).clamp(Fast(-1.), Fast(1.)), Fast(-1.));
+        assert_eq!(Fast(0.5).clamp(Fast(-1.), Fast(1.)), Fast(0.5));
+    }
+
+    #[test]
+    fn classify() {
+        assert!(Fast(-0f32).is_sign_negative() && Fast(-0f32).is_finite());
+        assert!(Fast(f64::NAN).is_nan() && !Fast(f64::INFINITY).is_finite());
+        assert_eq!(Fast(1e-310).classify(), FpCategory::Subnormal);
+    }
+
+    #[test]
+    fn ulps() {
+        assert_eq!(Fast(1.).next_up().next_down(), Fast(1.));
+        assert_eq!(Fast(-0.).ulps_between(Fast(0.)), 0);
+        assert_eq!(Fast(-5e-324).ulps_between(Fast(5e-324)), 2);
+        assert_eq!(Fast(1f32).ulps_between(Fast(2.)), 1 << 23);
+        assert_eq!(Fast(-f32::MAX).ulps_between(Fast(f32::MAX)), 2 * 0x7f7f_ffff);
+        assert_eq!(Fast(-0.).total_cmp(&Fast(0.)), Ordering::Less);
+        assert_eq!(Fast(2f32).total_cmp(&Fast(1.)), Ordering::Greater);
+    }
+
+    #[test]
+    fn euclid() {
+        let f = Fast::<f64>;
+        for (a, b, q, r) in [(7., 4., 1., 3.), (-7., 4., -2., 1.), (7., -4., -1., 3.),
+                             (-7., -4., 2., 1.), (-8., 4., -2., 0.), (0.5, 0.25, 2., 0.)] {
+            assert_eq!((f(a).div_euclid(f(b)), f(a).rem_euclid(f(b))), (f(q), f(r)));
+        }
+        #[cfg(feature = "num-traits")]
+        assert_eq!(num_traits::Euclid::rem_euclid(&f(-1.), &f(3.)), f(2.));
+    }
+
+    #[test]
+    fn midpoint() {
+        let f = Fast::<f32>;
+        assert_eq!(f(-3.).midpoint(f(4.)), f(0.5));
+        assert_eq!(f(f32::MAX).midpoint(f(-f32::MAX)), f(0.));
+        assert_eq!(f(f32::MAX).midpoint(f(f32::MAX / 2.)), f(f32::MAX * 0.75));
+        assert_eq!(f(f32::MAX).midpoint(f(1.)), f(f32::MAX / 2.));
+        let tiny = f(f32::from_bits(2));
+        assert_eq!(tiny.midpoint(f(0.)), f(f32::from_bits(1)));
+    }
+
+    #[test]
+    fn select() {
+        assert_eq!(Fast::select(true, Fast(1f64), Fast(2.)), Fast(1.));
+        assert_eq!(Fast::select(false, Fast(1f32), Fast(-0.)).to_bits(), 0x8000_0000);
+    }
+
+    #[test]
+    fn bits() {
+        let x = Fast(-2f64);
+        assert_eq!(x.to_bits(), 0xc000_0000_0000_0000);
+        assert_eq!(unsafe { Fast::<f64>::from_bits(x.to_bits()) }, x);
+        assert_eq!(libstd::format!("{:x}", Fast(1f32)), "3f800000");
+        assert_eq!(libstd::format!("{:#010X}", Fast(0.1f32)), "0x3DCCCCCD");
+    }
+
+    #[test]
+    fn approx_eq() {
+        assert!(Fast(1.).approx_eq(Fast(1. + 1e-10), Fast(1e-9), Fast(0.)));
+        assert!(!Fast(1.).approx_eq(Fast(1. + 1e-8), Fast(1e-9), Fast(0.)));
+        assert!(Fast(-1f32).approx_eq(Fast(-1.5), Fast(0.), Fast(0.5)));
+        assert!(!Fast(f64::MAX).approx_eq(Fast(-f64::MAX), Fast(1.), Fast(0.)));
+        assert!(Fast(f32::MAX).approx_eq(Fast(-f32::MAX), Fast(2.), Fast(0.)));
+    }
+
+    #[test]
+    fn map() {
+        unsafe {
+            assert_eq!(Fast(2.).map(|x: f64| x.powi(3)), Fast(8.));
+            assert_eq!(Fast(2.).zip_with(Fast(3.), f64::max), Fast(3.));
+        }
+    }
+
+    #[test]
+    fn angles() {
+        assert_eq!(Fast(std::f64::consts::PI).to_degrees(), Fast(180.));
+        assert_eq!(Fast(180f32).to_radians(), Fast(std::f32::consts::PI));
+    }
+
     #[test]
     fn assign_ops() {
         assign_op!(
@@ -0,0 +1,224 @@
+//! Solvers for small and structured linear systems.
+
+use crate::{Fast, FastFloat};
+
+/// Solve the tridiagonal system `A x = rhs` with the Thomas algorithm, writing `x` to `out`.
+///
+/// Row `i` of `A` is `lower[i - 1] x[i - 1] + diag[i] x[i] + upper[i] x[i + 1]`: `diag` has
+/// `n` elements and `lower` and `upper` have `n - 1`. `rhs` is used as scratch space and is
+/// overwritten.
+///
+/// The algorithm does not pivot, so it is stable for diagonally dominant or symmetric
+/// positive definite matrices; these are the systems of splines and of implicit diffusion
+/// steps. Return `false` if a pivot is zero, in which case `out` is unspecified.
+///
+/// ***Panics*** if the slices do not have the lengths above.
+///
+/// ```
+/// use fast_floats::linalg::solve_tridiagonal;
+/// use fast_floats::Fast;
+///
+/// let f = |x| unsafe { Fast::new(x) };
+/// // [[2, 1, 0], [1, 2, 1], [0, 1, 2]] x = [4, 8, 8]
+/// let mut rhs = [4., 8., 8.].map(f);
+/// let mut x = [f(0.); 3];
+/// assert!(solve_tridiagonal(&[f(1.); 2], &[f(2.); 3], &[f(1.); 2], &mut rhs, &mut x));
+/// for (x, expected) in x.iter().zip([1f64, 2., 3.]) {
+///     assert!((x.get() - expected).abs() < 1e-12);
+/// }
+/// ```
+pub fn solve_tridiagonal<F: FastFloat>(lower: &[Fast<F>], diag: &[Fast<F>], upper: &[Fast<F>],
+                                       rhs: &mut [Fast<F>], out: &mut [Fast<F>]) -> bool
+{
+    let n = diag.len();
+    assert!(rhs.len() == n && out.len() == n,
+            "solve_tridiagonal: diag, rhs and out must be of equal length");
+    assert!(lower.len() == n.saturating_sub(1) && upper.len() == n.saturating_sub(1),
+            "solve_tridiagonal: lower and upper must have one element less than diag");
+    if n == 0 {
+        return true;
+    }
+    let zero = Fast(F::ZERO);
+    // forward sweep: out holds the modified upper diagonal, rhs the modified right side
+    let mut pivot = diag[0];
+    for i in 0..n {
+        if i > 0 {
+            pivot = diag[i] - lower[i - 1] * out[i - 1];
+        }
+        if pivot == zero {
+            return false;
+        }
+        // divide instead of scaling by 1 / pivot, which overflows for a subnormal pivot
+        if i + 1 < n {
+            out[i] = upper[i] / pivot;
+        }
+        let prev = if i > 0 { lower[i - 1] * rhs[i - 1] } else { zero };
+        rhs[i] = (rhs[i] - prev) / pivot;
+    }
+    // back substitution, replacing the modified upper diagonal with the solution
+    out[n - 1] = rhs[n - 1];
+    for i in (0..n - 1).rev() {
+        out[i] = rhs[i] - out[i] * out[i + 1];
+    }
+    true
+}
+
+/// Compute the Cholesky factorization `A = L Lᵀ` of the symmetric positive definite
+/// `n × n` matrix `a` in place.
+///
+/// `a` is stored in row major order and only its lower triangle is read. On success it is
+/// overwritten with `L`, including zeros in the strict upper triangle. Return `false` if a
+/// pivot is not positive, which means that `A` is not positive definite (up to rounding); in
+/// that case `a` is unspecified.
+///
+/// The loops are meant for the small matrices of Kalman filters and Gaussian processes; for
+/// large matrices, a blocked factorization is much faster.
+///
+/// ***Panics*** if the length of `a` is not `n * n`.
+///
+/// ```
+/// use fast_floats::linalg::{cholesky, cholesky_solve};
+/// use fast_floats::Fast;
+///
+/// let f = |x| unsafe { Fast::new(x) };
+/// let mut a = [4., 2., 2., 5.].map(f);
+/// assert!(cholesky(&mut a, 2));
+/// assert_eq!(a, [2., 0., 1., 2.].map(f));
+///
+/// // [[4, 2], [2, 5]] x = [8, 12]
+/// let mut b = [8f64, 12.].map(f);
+/// cholesky_solve(&a, 2, &mut b);
+/// for (x, expected) in b.iter().zip([1f64, 2.]) {
+///     assert!((x.get() - expected).abs() < 1e-12);
+/// }
+/// ```
+pub fn cholesky<F: FastFloat>(a: &mut [Fast<F>], n: usize) -> bool {
+    assert_eq!(a.len(), n * n, "cholesky: matrix must have n * n elements");
+    let zero = Fast(F::ZERO);
+    for j in 0..n {
+        let (row_j, below) = a[j * n..].split_at_mut(n);
+        // l_jk for k < j are final, so compute the pivot first
+        let mut d = row_j[j];
+        for &l in &row_j[..j] {
+            d -= l * l;
+        }
+        if d <= zero {
+            return false;
+        }
+        let d = Fast(d.0.sqrt_strict());
+        row_j[j] = d;
+        row_j[j + 1..].fill(zero);
+        let inv = Fast(F::ONE) / d;
+        // l_ij = (a_ij - Σ_k l_ik l_jk) / l_jj for i > j
+        for row_i in below.chunks_exact_mut(n) {
+            let mut s = row_i[j];
+            for (&x, &y) in row_i[..j].iter().zip(&row_j[..j]) {
+                s -= x * y;
+            }
+            row_i[j] = s * inv;
+        }
+    }
+    true
+}
+
+/// Solve `L Lᵀ x = b` in place, given the factor `l` from [`cholesky`].
+///
+/// This is a forward substitution with `L` followed by a back substitution with `Lᵀ`; only
+/// the lower triangle of `l` is read.
+///
+/// ***Panics*** if the length of `l` is not `n * n` or the length of `b` is not `n`.
+pub fn cholesky_solve<F: FastFloat>(l: &[Fast<F>], n: usize, b: &mut [Fast<F>]) {
+    assert_eq!(l.len(), n * n, "cholesky_solve: matrix must have n * n elements");
+    assert_eq!(b.len(), n, "cholesky_solve: b must have n elements");
+    // L y = b
+    for i in 0..n {
+        let row = &l[i * n..i * n + i];
+        let mut s = b[i];
+        for (&x, &y) in row.iter().zip(&b[..i]) {
+            s -= x * y;
+        }
+        b[i] = s / l[i * n + i];
+    }
+    // Lᵀ x = y
+    for i in (0..n).rev() {
+        let mut s = b[i];
+        for k in i + 1..n {
+            s -= l[k * n + i] * b[k];
+        }
+        b[i] = s / l[i * n + i];
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tridiagonal() {
+        // 1-D Poisson matrix with a known solution
+        let n = 6;
+        let x: [Fast<f64>; 6] = std::array::from_fn(|i| Fast((i * i) as f64 - 2.));
+        let (lower, diag, upper) = ([Fast(-1.); 5], [Fast(2.); 6], [Fast(-1.); 5]);
+        let mut rhs: [Fast<f64>; 6] = std::array::from_fn(|i| {
+            let left = if i > 0 { x[i - 1] } else { Fast(0.) };
+            let right = if i + 1 < n { x[i + 1] } else { Fast(0.) };
+            Fast(2.) * x[i] - left - right
+        });
+        let mut out = [Fast(0.); 6];
+        assert!(solve_tridiagonal(&lower, &diag, &upper, &mut rhs, &mut out));
+        for (o, e) in out.iter().zip(&x) {
+            assert!((o.get() - e.get()).abs() < 1e-12, "{:?} != {:?}", o, e);
+        }
+
+        let mut rhs = [Fast(1.)];
+        assert!(solve_tridiagonal(&[], &[Fast(4.)], &[], &mut rhs, &mut out[..1]));
+        assert_eq!(out[0], Fast(0.25));
+        let mut rhs = [Fast(1.), Fast(1.)];
+        assert!(!solve_tridiagonal(&[Fast(1.)], &[Fast(0.), Fast(1.)], &[Fast(1.)], &mut rhs,
+                                   &mut out[..2]));
+        // a subnormal first pivot, whose reciprocal is not representable
+        let tiny = Fast(f64::MIN_POSITIVE * 2f64.powi(-38));
+        let mut rhs = [tiny + tiny, Fast(3.)];
+        assert!(solve_tridiagonal(&[Fast(1.)], &[tiny, Fast(2.)], &[tiny], &mut rhs,
+                                  &mut out[..2]));
+        assert_eq!(out[..2], [Fast(1.), Fast(1.)]);
+    }
+
+    #[test]
+    fn cholesky_spd() {
+        // A = B Bᵀ + I is positive definite
+        let n = 4;
+        let b: [Fast<f64>; 16] = std::array::from_fn(|i| Fast(((i * 7) % 5) as f64 - 2.));
+        let a: [Fast<f64>; 16] = std::array::from_fn(|i| {
+            let (r, c) = (i / n, i % n);
+            let dot = (0..n).fold(Fast(0.), |s, k| s + b[r * n + k] * b[c * n + k]);
+            if r == c { dot + Fast(1.) } else { dot }
+        });
+        let mut l = a;
+        assert!(cholesky(&mut l, n));
+        for r in 0..n {
+            for c in 0..n {
+                let llt = (0..n).fold(Fast(0.), |s, k| s + l[r * n + k] * l[c * n + k]);
+                assert!((llt.get() - a[r * n + c].get()).abs() < 1e-12);
+            }
+        }
+        let x = [Fast(1.), Fast(-2.), Fast(0.5), Fast(3.)];
+        let mut rhs: [Fast<f64>; 4] = std::array::from_fn(|r| {
+            (0..n).fold(Fast(0.), |s, k| s + a[r * n + k] * x[k])
+        });
+        cholesky_solve(&l, n, &mut rhs);
+        for (o, e) in rhs.iter().zip(&x) {
+            assert!((o.get() - e.get()).abs() < 1e-12, "{:?} != {:?}", o, e);
+        }
+
+        // f32 uses the correctly rounded square root also with the `micromath` feature
+        let mut l = [Fast(2f32), Fast(1.), Fast(1.), Fast(2.)];
+        let mut rhs = [Fast(3f32), Fast(3.)];
+        assert!(cholesky(&mut l, 2));
+        cholesky_solve(&l, 2, &mut rhs);
+        assert!(rhs.iter().all(|x| (x.get() - 1.).abs() < 1e-6), "{:?}", rhs);
+
+        let mut indefinite = [Fast(1.), Fast(2.), Fast(2.), Fast(1.)];
+        assert!(!cholesky(&mut indefinite, 2));
+    }
+}
@@ -0,0 +1,67 @@
+use crate::{Fast, FastFloat};
+
+/// A logarithm base, with the reciprocal of its natural logarithm precomputed.
+///
+/// Each [`log`](LogBase::log) is then one natural logarithm and one multiplication.
+///
+/// ```
+/// use fast_floats::{Fast, LogBase};
+///
+/// let f = |x| unsafe { Fast::new(x) };
+/// let log2 = LogBase::new(f(2.));
+/// assert!((log2.log(f(8.)) - 3.).abs() < f(1e-12));
+/// assert!((LogBase::base2().log(f(0.25)) + 2.).abs() < f(1e-12));
+/// ```
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct LogBase<F> {
+    inv_ln_base: Fast<F>,
+}
+
+impl<F: FastFloat> LogBase<F> {
+    /// Create a logarithm base.
+    ///
+    /// The base must be positive and not one, otherwise the reciprocal of its logarithm is
+    /// not valid in `Fast`.
+    #[inline]
+    pub fn new(base: Fast<F>) -> Self {
+        LogBase { inv_ln_base: Fast(F::ONE) / base.ln() }
+    }
+
+    /// The base 2
+    pub fn base2() -> Self {
+        LogBase { inv_ln_base: Fast(F::from_f64(std::f64::consts::LOG2_E)) }
+    }
+
+    /// The base 10
+    pub fn base10() -> Self {
+        LogBase { inv_ln_base: Fast(F::from_f64(std::f64::consts::LOG10_E)) }
+    }
+
+    /// Return the reciprocal of the natural logarithm of the base
+    pub fn inv_ln_base(&self) -> Fast<F> { self.inv_ln_base }
+
+    /// Return the logarithm of `x` in this base
+    ///
+    /// See [`Fast::ln`] for the values of `x` that are valid.
+    #[inline(always)]
+    pub fn log(&self, x: Fast<F>) -> Fast<F> { x.ln() * self.inv_ln_base }
+
+    /// Return the logarithm of `x` in this base, using [`approx::ln`](crate::approx::ln)
+    #[inline(always)]
+    pub fn log_approx(&self, x: Fast<F>) -> Fast<F> { crate::approx::ln(x) * self.inv_ln_base }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::assert_f32_math;
+
+    #[test]
+    fn bases() {
+        let log10 = LogBase::base10();
+        assert_f32_math(log10.log(Fast(100f32)), 2.);
+        assert!((log10.log_approx(Fast(1e-3)) + 3.).abs() < Fast(1e-6));
+        let inv = LogBase::new(Fast(10.)).inv_ln_base() - LogBase::<f64>::base10().inv_ln_base();
+        assert!(inv.abs() < Fast(1e-16));
+    }
+}
@@ -0,0 +1,298 @@
+use std::ops::{Add, Index, IndexMut, Mul, Sub};
+
+use crate::{Fast, FastFloat};
+
+/// A small, stack allocated `R × C` matrix of fast values, stored row by row.
+///
+/// Products are computed with fast multiply-adds that the compiler may contract to FMA.
+///
+/// ```
+/// use fast_floats::{Fast, FastMatrix};
+///
+/// let f = |x| unsafe { Fast::new(x) };
+/// let a = FastMatrix::new([[f(1.), f(2.)],
+///                          [f(3.), f(4.)]]);
+/// assert_eq!(a * [f(1.), f(1.)], [f(3.), f(7.)]);
+/// assert_eq!(a.transpose()[(0, 1)], f(3.));
+/// ```
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct FastMatrix<F, const R: usize, const C: usize> {
+    rows: [[Fast<F>; C]; R],
+}
+
+impl<F, const R: usize, const C: usize> FastMatrix<F, R, C> {
+    /// Create a matrix from its rows
+    pub fn new(rows: [[Fast<F>; C]; R]) -> Self {
+        FastMatrix { rows }
+    }
+
+    /// Return the rows of the matrix
+    pub fn rows(&self) -> &[[Fast<F>; C]; R] { &self.rows }
+
+    /// Return the rows of the matrix
+    pub fn into_rows(self) -> [[Fast<F>; C]; R] { self.rows }
+}
+
+impl<F: FastFloat, const R: usize, const C: usize> FastMatrix<F, R, C> {
+    /// Return the matrix with all elements zero
+    pub fn zero() -> Self {
+        FastMatrix { rows: [[Fast(F::ZERO); C]; R] }
+    }
+
+    /// Return the transpose of the matrix
+    pub fn transpose(&self) -> FastMatrix<F, C, R> {
+        FastMatrix { rows: std::array::from_fn(|j| std::array::from_fn(|i| self.rows[i][j])) }
+    }
+
+    /// Return the matrix product `self * rhs`
+    pub fn matmul<const K: usize>(&self, rhs: &FastMatrix<F, C, K>) -> FastMatrix<F, R, K> {
+        let mut out = FastMatrix::zero();
+        // accumulate scaled rows of `rhs`, which lets the inner loop run over contiguous rows
+        for (out_row, row) in out.rows.iter_mut().zip(&self.rows) {
+            for (&a, rhs_row) in row.iter().zip(&rhs.rows) {
+                for (o, &b) in out_row.iter_mut().zip(rhs_row) {
+                    *o = a * b + *o;
+                }
+            }
+        }
+        out
+    }
+
+    /// Return the matrix-vector product `self * x`
+    pub fn mul_vec(&self, x: &[Fast<F>; C]) -> [Fast<F>; R] {
+        std::array::from_fn(|i| {
+            self.rows[i].iter().zip(x).fold(Fast(F::ZERO), |acc, (&a, &b)| a * b + acc)
+        })
+    }
+}
+
+impl<F: FastFloat, const N: usize> FastMatrix<F, N, N> {
+    /// Return the identity matrix
+    pub fn identity() -> Self {
+        let mut m = Self::zero();
+        for i in 0..N {
+            m.rows[i][i] = Fast(F::ONE);
+        }
+        m
+    }
+}
+
+/// Return `a d - b c`, the determinant of `[[a, b], [c, d]]`
+#[inline]
+fn det2<F: FastFloat>(a: Fast<F>, b: Fast<F>, c: Fast<F>, d: Fast<F>) -> Fast<F> {
+    a * d - b * c
+}
+
+/// Return `adj / det`, or `None` if `det` is zero or a quotient overflows
+///
+/// Each element is divided by `det`, since `1 / det` can overflow when the inverse does not.
+#[inline]
+fn scale_adjugate<F: FastFloat, const N: usize>(adj: [[Fast<F>; N]; N], det: Fast<F>)
+    -> Option<FastMatrix<F, N, N>>
+{
+    if det == Fast(F::ZERO) {
+        return None;
+    }
+    let mut rows = [[Fast(F::ZERO); N]; N];
+    for (row, adj_row) in rows.iter_mut().zip(&adj) {
+        for (x, &a) in row.iter_mut().zip(adj_row) {
+            let q = F::div_strict(a.0, det.0);
+            if !q.is_finite() {
+                return None;
+            }
+            *x = Fast(q);
+        }
+    }
+    Some(FastMatrix { rows })
+}
+
+/// Closed form determinant and inverse, by cofactor expansion.
+///
+/// The inverse is the adjugate divided by the determinant; it is `None` if the determinant
+/// is zero or if an element of the inverse overflows. The closed forms do not pivot, so for ill-conditioned matrices they lose more
+/// accuracy than an LU decomposition would.
+impl<F: FastFloat> FastMatrix<F, 2, 2> {
+    /// Return the determinant
+    pub fn determinant(&self) -> Fast<F> {
+        let [[a, b], [c, d]] = self.rows;
+        det2(a, b, c, d)
+    }
+
+    /// Return the inverse, or `None` if the matrix is singular
+    pub fn inverse(&self) -> Option<Self> {
+        let [[a, b], [c, d]] = self.rows;
+        scale_adjugate([[d, -b], [-c, a]], self.determinant())
+    }
+}
+
+/// Closed form determinant and inverse, see the 2 × 2 case.
+impl<F: FastFloat> FastMatrix<F, 3, 3> {
+    /// Return the determinant
+    pub fn determinant(&self) -> Fast<F> {
+        let [[a, b, c], [d, e, f], [g, h, i]] = self.rows;
+        a * det2(e, f, h, i) - b * det2(d, f, g, i) + c * det2(d, e, g, h)
+    }
+
+    /// Return the inverse, or `None` if the matrix is singular
+    pub fn inverse(&self) -> Option<Self> {
+        let [[a, b, c], [d, e, f], [g, h, i]] = self.rows;
+        let (c00, c01, c02) = (det2(e, f, h, i), det2(f, d, i, g), det2(d, e, g, h));
+        let det = a * c00 + b * c01 + c * c02;
+        scale_adjugate([[c00, det2(c, b, i, h), det2(b, c, e, f)],
+                        [c01, det2(a, c, g, i), det2(c, a, f, d)],
+                        [c02, det2(b, a, h, g), det2(a, b, d, e)]], det)
+    }
+}
+
+/// Closed form determinant and inverse, see the 2 × 2 case.
+///
+/// The 2 × 2 minors of the first two and the last two rows are computed once and shared
+/// by the determinant and all cofactors.
+impl<F: FastFloat> FastMatrix<F, 4, 4> {
+    /// Return the 2 × 2 minors of rows 0, 1 and of rows 2, 3, by column pair
+    #[inline]
+    fn minors(&self) -> ([Fast<F>; 6], [Fast<F>; 6]) {
+        let m = &self.rows;
+        let pair = |r: usize, i: usize, j: usize| det2(m[r][i], m[r][j], m[r + 1][i], m[r + 1][j]);
+        const COLS: [(usize, usize); 6] = [(0, 1), (0, 2), (0, 3), (1, 2), (1, 3), (2, 3)];
+        (COLS.map(|(i, j)| pair(0, i, j)), COLS.map(|(i, j)| pair(2, i, j)))
+    }
+
+    /// Return the determinant
+    pub fn determinant(&self) -> Fast<F> {
+        let (s, c) = self.minors();
+        s[0] * c[5] - s[1] * c[4] + s[2] * c[3] + s[3] * c[2] - s[4] * c[1] + s[5] * c[0]
+    }
+
+    /// Return the inverse, or `None` if the matrix is singular
+    pub fn inverse(&self) -> Option<Self> {
+        let m = &self.rows;
+        let (s, c) = self.minors();
+        let det = s[0] * c[5] - s[1] * c[4] + s[2] * c[3] + s[3] * c[2] - s[4] * c[1]
+            + s[5] * c[0];
+        let adj = [
+            [m[1][1] * c[5] - m[1][2] * c[4] + m[1][3] * c[3],
+             -m[0][1] * c[5] + m[0][2] * c[4] - m[0][3] * c[3],
+             m[3][1] * s[5] - m[3][2] * s[4] + m[3][3] * s[3],
+             -m[2][1] * s[5] + m[2][2] * s[4] - m[2][3] * s[3]],
+            [-m[1][0] * c[5] + m[1][2] * c[2] - m[1][3] * c[1],
+             m[0][0] * c[5] - m[0][2] * c[2] + m[0][3] * c[1],
+             -m[3][0] * s[5] + m[3][2] * s[2] - m[3][3] * s[1],
+             m[2][0] * s[5] - m[2][2] * s[2] + m[2][3] * s[1]],
+            [m[1][0] * c[4] - m[1][1] * c[2] + m[1][3] * c[0],
+             -m[0][0] * c[4] + m[0][1] * c[2] - m[0][3] * c[0],
+             m[3][0] * s[4] - m[3][1] * s[2] + m[3][3] * s[0],
+             -m[2][0] * s[4] + m[2][1] * s[2] - m[2][3] * s[0]],
+            [-m[1][0] * c[3] + m[1][1] * c[1] - m[1][2] * c[0],
+             m[0][0] * c[3] - m[0][1] * c[1] + m[0][2] * c[0],
+             -m[3][0] * s[3] + m[3][1] * s[1] - m[3][2] * s[0],
+             m[2][0] * s[3] - m[2][1] * s[1] + m[2][2] * s[0]],
+        ];
+        scale_adjugate(adj, det)
+    }
+}
+
+impl<F, const R: usize, const C: usize> Index<(usize, usize)> for FastMatrix<F, R, C> {
+    type Output = Fast<F>;
+    fn index(&self, (i, j): (usize, usize)) -> &Fast<F> { &self.rows[i][j] }
+}
+
+impl<F, const R: usize, const C: usize> IndexMut<(usize, usize)> for FastMatrix<F, R, C> {
+    fn index_mut(&mut self, (i, j): (usize, usize)) -> &mut Fast<F> { &mut self.rows[i][j] }
+}
+
+impl<F: FastFloat, const R: usize, const C: usize, const K: usize> Mul<FastMatrix<F, C, K>>
+    for FastMatrix<F, R, C>
+{
+    type Output = FastMatrix<F, R, K>;
+    #[inline]
+    fn mul(self, rhs: FastMatrix<F, C, K>) -> Self::Output { self.matmul(&rhs) }
+}
+
+impl<F: FastFloat, const R: usize, const C: usize> Mul<[Fast<F>; C]> for FastMatrix<F, R, C> {
+    type Output = [Fast<F>; R];
+    #[inline]
+    fn mul(self, rhs: [Fast<F>; C]) -> Self::Output { self.mul_vec(&rhs) }
+}
+
+macro_rules! impl_elementwise {
+    ($($name:ident, $method:ident;)*) => {
+        $(
+        impl<F: FastFloat, const R: usize, const C: usize> $name for FastMatrix<F, R, C> {
+            type Output = Self;
+            #[inline]
+            fn $method(mut self, rhs: Self) -> Self {
+                for (row, rhs_row) in self.rows.iter_mut().zip(&rhs.rows) {
+                    for (x, &y) in row.iter_mut().zip(rhs_row) {
+                        *x = x.$method(y);
+                    }
+                }
+                self
+            }
+        }
+        )*
+    }
+}
+
+impl_elementwise! {
+    Add, add;
+    Sub, sub;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn products() {
+        let a = FastMatrix::new([[Fast(1.), Fast(2.), Fast(3.)],
+                                 [Fast(4.), Fast(5.), Fast(6.)]]);
+        let b = a.transpose();
+        assert_eq!(a * b, FastMatrix::new([[Fast(14.), Fast(32.)],
+                                           [Fast(32.), Fast(77.)]]));
+        assert_eq!(FastMatrix::identity() * a, a);
+        assert_eq!(a * [Fast(1.), Fast(0.), Fast(-1.)], [Fast(-2.), Fast(-2.)]);
+        assert_eq!(a + a - a, a);
+    }
+
+    fn assert_identity<const N: usize>(m: FastMatrix<f64, N, N>) {
+        for i in 0..N {
+            for j in 0..N {
+                let e = if i == j { 1. } else { 0. };
+                assert!((m[(i, j)].get() - e).abs() < 1e-12, "{:?}", m);
+            }
+        }
+    }
+
+    #[test]
+    fn inverses() {
+        let a = FastMatrix::new([[Fast(4.), Fast(7.)], [Fast(2.), Fast(6.)]]);
+        assert_eq!(a.determinant(), Fast(10.));
+        assert_identity(a * a.inverse().unwrap());
+
+        let b = FastMatrix::new([[Fast(2.), Fast(-1.), Fast(0.)],
+                                 [Fast(1.), Fast(3.), Fast(2.)],
+                                 [Fast(0.), Fast(5.), Fast(-4.)]]);
+        assert_eq!(b.determinant(), Fast(-48.));
+        assert_identity(b * b.inverse().unwrap());
+
+        let c = FastMatrix::new([[Fast(1.), Fast(2.), Fast(0.), Fast(-1.)],
+                                 [Fast(3.), Fast(0.), Fast(1.), Fast(2.)],
+                                 [Fast(-2.), Fast(1.), Fast(4.), Fast(0.)],
+                                 [Fast(0.), Fast(1.), Fast(2.), Fast(5.)]]);
+        assert_identity(c * c.inverse().unwrap());
+        assert_identity(c.inverse().unwrap() * c);
+        assert_eq!(c.transpose().determinant(), c.determinant());
+
+        let singular = FastMatrix::new([[Fast(1f32), Fast(2.)], [Fast(2.), Fast(4.)]]);
+        assert_eq!(singular.inverse(), None);
+        // the determinant is subnormal and its reciprocal overflows, the inverse does not
+        let tiny = Fast(2f64.powi(-530));
+        let d = FastMatrix::new([[tiny, Fast(0.)], [Fast(0.), tiny]]);
+        let huge = Fast(2f64.powi(530));
+        assert_eq!(d.inverse(), Some(FastMatrix::new([[huge, Fast(0.)], [Fast(0.), huge]])));
+        // 1 / 2⁻¹⁰²⁴ is not representable
+        let d = FastMatrix::new([[Fast(f64::MIN_POSITIVE / 4.), Fast(0.)], [Fast(0.), Fast(1.)]]);
+        assert_eq!(d.inverse(), None);
+    }
+}
@@ -0,0 +1,236 @@
+//! Kernels for neural network inference.
+//!
+//! The kernels accumulate with the fast-math flags, so their reductions are vectorized and
+//! can differ in the last bits from a sequential implementation.
+//!
+//! ```
+//! use fast_floats::{ml, Fast};
+//!
+//! let f = |x| unsafe { Fast::new(x) };
+//! let x = [1., 2., 3., 4.].map(f);
+//! let mut out = [f(0.); 4];
+//! ml::layer_norm(&x, &[f(1.); 4], &[f(0.); 4], f(1e-12), &mut out);
+//! assert!(out[0] < f(0.) && out[3] > f(0.));
+//! assert!(ml::mean_var(&out).0.abs() < f(1e-9));
+//! ```
+
+use std::f64::consts::{FRAC_1_SQRT_2, FRAC_2_SQRT_PI};
+use std::intrinsics::round_ties_even_f32;
+
+use crate::approx;
+use crate::{Fast, FastFloat};
+
+// beyond this, the tanh in the GELU approximation is one to working precision
+const GELU_MAX_ARG: f64 = 10.;
+
+/// Return the mean and the (population) variance of `xs`, in one pass.
+///
+/// The values are shifted by the first element while they are accumulated, which avoids the
+/// cancellation of the textbook one pass formula when the mean is large compared with the
+/// spread. Return zeros if `xs` is empty.
+pub fn mean_var<F: FastFloat>(xs: &[Fast<F>]) -> (Fast<F>, Fast<F>) {
+    let zero = Fast(F::ZERO);
+    let Some(&shift) = xs.first() else { return (zero, zero) };
+    let (s1, s2) = xs.iter().fold((zero, zero), |(s1, s2), &x| {
+        let d = x - shift;
+        (s1 + d, d * d + s2)
+    });
+    let inv_n = Fast(F::ONE) / F::from_f64(xs.len() as f64);
+    let d = s1 * inv_n;
+    (shift + d, (s2 * inv_n - d * d).max(zero))
+}
+
+/// Compute layer normalization: `out[i] = (x[i] - mean) / sqrt(var + eps) * gamma[i] + beta[i]`,
+/// where `mean` and `var` are the mean and variance of `x`.
+///
+/// The statistics take one pass over `x`, see [`mean_var`], and the affine output one more
+/// pass with a multiply-add per element.
+///
+/// ***Panics*** if the slices are not of equal length, or if `eps` is not positive.
+pub fn layer_norm<F: FastFloat>(x: &[Fast<F>], gamma: &[Fast<F>], beta: &[Fast<F>],
+                                eps: Fast<F>, out: &mut [Fast<F>])
+{
+    let n = x.len();
+    assert!(gamma.len() == n && beta.len() == n && out.len() == n,
+            "layer_norm: slices must be of equal length");
+    assert!(eps > Fast(F::ZERO), "layer_norm: eps must be positive, got {:?}", eps);
+    let (mean, var) = mean_var(x);
+    let inv_std = Fast(F::ONE) / Fast((var + eps).0.sqrt_strict());
+    for i in 0..n {
+        let g = gamma[i] * inv_std;
+        out[i] = (x[i] - mean) * g + beta[i];
+    }
+}
+
+/// Compute batch normalization for inference:
+/// `out = (x - mean[c]) / sqrt(var[c] + eps) * gamma[c] + beta[c]` for each channel `c`.
+///
+/// `x` is laid out channel by channel (as in NCHW for one image): it is split into
+/// `mean.len()` equal blocks, one per channel. The scale and shift of each channel are
+/// computed once, so each element is one multiply-add.
+///
+/// ***Panics*** if the channel slices are not of equal length, if `x` and `out` differ in
+/// length or can not be split evenly into the channels, or if `eps` is not positive.
+pub fn batch_norm_inference<F: FastFloat>(x: &[Fast<F>], mean: &[Fast<F>], var: &[Fast<F>],
+                                          gamma: &[Fast<F>], beta: &[Fast<F>], eps: Fast<F>,
+                                          out: &mut [Fast<F>])
+{
+    let channels = mean.len();
+    assert!(var.len() == channels && gamma.len() == channels && beta.len() == channels,
+            "batch_norm_inference: channel slices must be of equal length");
+    assert_eq!(x.len(), out.len(), "batch_norm_inference: x and out must be of equal length");
+    assert!(eps > Fast(F::ZERO), "batch_norm_inference: eps must be positive, got {:?}", eps);
+    if x.is_empty() {
+        return;
+    }
+    assert!(x.len().is_multiple_of(channels),
+            "batch_norm_inference: length {} is not a multiple of {} channels",
+            x.len(), channels);
+    let block = x.len() / channels;
+    let blocks = x.chunks_exact(block).zip(out.chunks_exact_mut(block));
+    for (c, (xs, out)) in blocks.enumerate() {
+        let scale = gamma[c] / Fast((var[c] + eps).0.sqrt_strict());
+        let shift = beta[c] - mean[c] * scale;
+        for (o, &x) in out.iter_mut().zip(xs) {
+            *o = x.mul_add(scale, shift);
+        }
+    }
+}
+
+/// Compute the rectified linear unit `out[i] = max(x[i], 0)`.
+///
+/// ***Panics*** if the slices are not of equal length.
+pub fn relu<F: FastFloat>(x: &[Fast<F>], out: &mut [Fast<F>]) {
+    assert_eq!(x.len(), out.len(), "relu: slices must be of equal length");
+    for (o, &x) in out.iter_mut().zip(x) {
+        *o = x.max(Fast(F::ZERO));
+    }
+}
+
+/// Compute the Gaussian error linear unit, with the tanh approximation:
+/// `out[i] = x/2 (1 + tanh(√(2/π) (x + 0.044715 x³)))`, using [`approx::tanh`].
+///
+/// ***Panics*** if the slices are not of equal length.
+pub fn gelu<F: FastFloat>(x: &[Fast<F>], out: &mut [Fast<F>]) {
+    assert_eq!(x.len(), out.len(), "gelu: slices must be of equal length");
+    let k = Fast(F::from_f64(FRAC_2_SQRT_PI * FRAC_1_SQRT_2));
+    let c = Fast(F::from_f64(0.044715));
+    let max = Fast(F::from_f64(GELU_MAX_ARG));
+    let half = Fast(F::from_f64(0.5));
+    for (o, &x) in out.iter_mut().zip(x) {
+        // clamped so that the cube can not overflow
+        let y = x.clamp(-max, max);
+        let t = approx::tanh(k * (c * y * y * y + y));
+        *o = half * x * (t + F::ONE);
+    }
+}
+
+/// Compute the sigmoid linear unit `out[i] = x / (1 + e^-x)`, using [`approx::exp`].
+///
+/// ***Panics*** if the slices are not of equal length.
+pub fn silu<F: FastFloat>(x: &[Fast<F>], out: &mut [Fast<F>]) {
+    assert_eq!(x.len(), out.len(), "silu: slices must be of equal length");
+    for (o, &x) in out.iter_mut().zip(x) {
+        *o = x / (approx::exp(-x) + F::ONE);
+    }
+}
+
+/// Quantize `x` to `i8` with an affine mapping: `out[i] = round(x[i] / scale) + zero_point`,
+/// rounded to nearest, ties to even, and saturated to the range of `i8`.
+///
+/// Each element is divided by `scale`, so a subnormal `scale` works as well.
+///
+/// ***Panics*** if the slices are not of equal length, or if `scale` is not positive.
+pub fn quantize(x: &[Fast<f32>], scale: Fast<f32>, zero_point: i8, out: &mut [i8]) {
+    assert_eq!(x.len(), out.len(), "quantize: slices must be of equal length");
+    assert!(scale > Fast(0.), "quantize: scale must be positive, got {:?}", scale);
+    // plain arithmetic, since the scaled value can overflow before it is saturated; and a
+    // division, since 1 / scale overflows for a subnormal scale
+    let zero_point = zero_point as f32;
+    for (o, x) in out.iter_mut().zip(x) {
+        *o = (round_ties_even_f32(x.0 / scale.0) + zero_point) as i8;
+    }
+}
+
+/// Dequantize `q` from `i8` with an affine mapping: `out[i] = (q[i] - zero_point) * scale`.
+///
+/// ***Panics*** if the slices are not of equal length.
+pub fn dequantize(q: &[i8], scale: Fast<f32>, zero_point: i8, out: &mut [Fast<f32>]) {
+    assert_eq!(q.len(), out.len(), "dequantize: slices must be of equal length");
+    for (o, &q) in out.iter_mut().zip(q) {
+        *o = Fast((q as i16 - zero_point as i16) as f32) * scale;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate std as libstd;
+
+    use super::*;
+
+    #[test]
+    fn normalization() {
+        assert_eq!(mean_var::<f64>(&[]), (Fast(0.), Fast(0.)));
+        let x = [1e8 + 1., 1e8 + 2., 1e8 + 3., 1e8 + 4.].map(Fast);
+        assert_eq!(mean_var(&x), (Fast(1e8 + 2.5), Fast(1.25)));
+
+        let mut out = [Fast(0f64); 4];
+        layer_norm(&x, &[Fast(2.); 4], &[Fast(1.); 4], Fast(1e-300), &mut out);
+        let s = 2. / 1.25f64.sqrt();
+        let expected = [1. - 1.5 * s, 1. - 0.5 * s, 1. + 0.5 * s, 1. + 1.5 * s];
+        for (o, e) in out.iter().zip(expected) {
+            assert!((o.get() - e).abs() < 1e-12, "{:?} != {}", o, e);
+        }
+    }
+
+    #[test]
+    fn activations() {
+        let x = [-1e30, -3., -0.5, 0., 0.5, 3., 1e30].map(Fast);
+        let mut out = [Fast(0f64); 7];
+        relu(&x, &mut out);
+        assert_eq!(out, [0., 0., 0., 0., 0.5, 3., 1e30].map(Fast));
+
+        // the approximations are accurate to about 1e-8
+        gelu(&x, &mut out);
+        for (o, &x) in out.iter().zip(&x) {
+            let x = x.get();
+            let k = (2. / std::f64::consts::PI).sqrt();
+            let t = libstd::primitive::f64::tanh(k * (x + 0.044715 * x * x * x));
+            let e = 0.5 * x * (1. + t);
+            assert!((o.get() - e).abs() <= 1e-8 * e.abs().max(1.), "{:?} != {}", o, e);
+        }
+
+        silu(&x, &mut out);
+        for (o, &x) in out.iter().zip(&x) {
+            let x = x.get();
+            let e = x / (1. + libstd::primitive::f64::exp(-x));
+            assert!((o.get() - e).abs() <= 1e-8 * e.abs().max(1.), "{:?} != {}", o, e);
+        }
+    }
+
+    #[test]
+    fn quantization() {
+        let x = [-100., -1., -0.25, 0., 0.25, 0.75, 1., 100.].map(Fast);
+        let mut q = [0i8; 8];
+        quantize(&x, Fast(0.5), 10, &mut q);
+        assert_eq!(q, [-128, 8, 10, 10, 10, 12, 12, 127]);
+        let mut y = [Fast(0f32); 8];
+        dequantize(&q, Fast(0.5), 10, &mut y);
+        assert_eq!(y, [-69., -1., 0., 0., 0., 1., 1., 58.5].map(Fast));
+        // the smallest subnormal scale
+        let tiny = f32::from_bits(1);
+        quantize(&[Fast(0.), Fast(tiny * 5.), Fast(-1.)], Fast(tiny), 3, &mut q[..3]);
+        assert_eq!(q[..3], [3, 8, -128]);
+    }
+
+    #[test]
+    fn batch_norm() {
+        let x = [1., 2., 3., 4., 5., 6.].map(Fast);
+        let mut out = [Fast(0f64); 6];
+        batch_norm_inference(&x, &[Fast(2.), Fast(5.)], &[Fast(4.), Fast(0.25)],
+                             &[Fast(1.), Fast(2.)], &[Fast(0.), Fast(1.)], Fast(1e-300),
+                             &mut out);
+        assert_eq!(out, [-0.5, 0., 0.5, -3., 1., 5.].map(Fast));
+        batch_norm_inference(&[], &[], &[], &[], &[], Fast(1.), &mut []);
+    }
+}
@@ -0,0 +1,73 @@
+//! Parallel slice algorithms, using the `rayon` crate.
+//!
+//! This module requires the `rayon` feature. The work is split into blocks of a fixed size,
+//! [`BLOCK_SIZE`], so the results do not depend on the number of threads.
+
+use alloc::vec::Vec;
+
+use rayon::prelude::*;
+
+use crate::slice;
+use crate::{Fast, FastFloat};
+
+/// The number of elements in each block of work
+pub const BLOCK_SIZE: usize = 1 << 14;
+
+/// Compute the cumulative sum of `xs` into `out` in parallel:
+/// `out[i] = xs[0] + … + xs[i]`.
+///
+/// This is the two pass block scan: first each block is scanned in parallel, then the
+/// totals of the preceding blocks are added to each block in parallel. The running sum is
+/// associated differently from [`slice::cumsum`], so the results can differ in the last bits.
+///
+/// ***Panics*** if the slices are not of equal length.
+///
+/// ```
+/// use fast_floats::{par, Fast};
+///
+/// let xs = vec![unsafe { Fast::new(1.) }; 100_000];
+/// let mut out = vec![unsafe { Fast::new(0.) }; 100_000];
+/// par::cumsum(&xs, &mut out);
+/// assert_eq!(out[99_999].get(), 100_000.);
+/// ```
+pub fn cumsum<F: FastFloat + Send + Sync>(xs: &[Fast<F>], out: &mut [Fast<F>]) {
+    assert_eq!(xs.len(), out.len(), "cumsum: slices must be of equal length");
+    if xs.len() <= BLOCK_SIZE {
+        return slice::cumsum(xs, out);
+    }
+    let mut offsets: Vec<_> = xs.par_chunks(BLOCK_SIZE).zip(out.par_chunks_mut(BLOCK_SIZE))
+        .map(|(xs, out)| {
+            slice::cumsum(xs, out);
+            out[out.len() - 1]
+        })
+        .collect();
+    // the exclusive scan of the block totals
+    let mut acc = Fast(F::ZERO);
+    for x in &mut offsets {
+        (*x, acc) = (acc, acc + *x);
+    }
+    out.par_chunks_mut(BLOCK_SIZE).zip(offsets).skip(1).for_each(|(out, offset)| {
+        for x in out {
+            *x += offset;
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::vec;
+
+    #[test]
+    fn prefix_sum() {
+        let n = 3 * BLOCK_SIZE + 17;
+        let xs: Vec<_> = (0..n).map(|i| Fast((i % 7) as f64)).collect();
+        let (mut out, mut expected) = (vec![Fast(0.); n], vec![Fast(0.); n]);
+        cumsum(&xs, &mut out);
+        slice::cumsum(&xs, &mut expected);
+        // the sums are integers, so they are exact in any order
+        assert_eq!(out, expected);
+        cumsum(&xs[..3], &mut out[..3]);
+        assert_eq!(&out[..3], [Fast(0.), Fast(1.), Fast(3.)]);
+    }
+}
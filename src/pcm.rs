@@ -0,0 +1,124 @@
+//! Conversion between integer PCM samples and `f32` samples.
+//!
+//! Float samples are in the range `-1 <= x < 1`: integer samples are scaled by `2^-15`
+//! (`i16`) or `2^-31` (`i32`). Conversion back to integers rounds to nearest, ties to even,
+//! and saturates, so that out of range float samples clip to the integer range.
+//!
+//! The `_dithered` converters add a dither value, in units of the integer sample's least
+//! significant bit, before rounding. The dither source is a closure, so any noise shape can
+//! be used; for example, TPDF dither is the sum of two uniform values in `-0.5..0.5`.
+//!
+//! ```
+//! use fast_floats::{pcm, Fast};
+//!
+//! let mut x = [unsafe { Fast::new(0f32) }; 3];
+//! pcm::from_i16(&[-32768, 0, 16384], &mut x);
+//! assert_eq!(x.map(Fast::get), [-1., 0., 0.5]);
+//!
+//! let mut y = [0i16; 3];
+//! pcm::to_i16(&x, &mut y);
+//! assert_eq!(y, [-32768, 0, 16384]);
+//! ```
+
+use std::intrinsics::round_ties_even_f32;
+
+use crate::Fast;
+
+const I16_SCALE: f32 = 32768.;
+const I32_SCALE: f32 = 2147483648.;
+
+/// Convert `i16` samples to float samples in `-1 <= x < 1`.
+///
+/// ***Panics*** if the slices are not of equal length.
+pub fn from_i16(input: &[i16], out: &mut [Fast<f32>]) {
+    assert_eq!(input.len(), out.len(), "from_i16: slices must be of equal length");
+    for (o, &x) in out.iter_mut().zip(input) {
+        *o = Fast(x as f32 * (1. / I16_SCALE));
+    }
+}
+
+/// Convert `i32` samples to float samples in `-1 <= x < 1`, rounded to `f32` precision.
+///
+/// ***Panics*** if the slices are not of equal length.
+pub fn from_i32(input: &[i32], out: &mut [Fast<f32>]) {
+    assert_eq!(input.len(), out.len(), "from_i32: slices must be of equal length");
+    for (o, &x) in out.iter_mut().zip(input) {
+        *o = Fast(x as f32 * (1. / I32_SCALE));
+    }
+}
+
+/// Convert float samples to `i16` samples, rounding and saturating.
+///
+/// ***Panics*** if the slices are not of equal length.
+pub fn to_i16(input: &[Fast<f32>], out: &mut [i16]) {
+    assert_eq!(input.len(), out.len(), "to_i16: slices must be of equal length");
+    for (o, x) in out.iter_mut().zip(input) {
+        *o = round_ties_even_f32(x.0 * I16_SCALE) as i16;
+    }
+}
+
+/// Convert float samples to `i32` samples, rounding and saturating.
+///
+/// ***Panics*** if the slices are not of equal length.
+pub fn to_i32(input: &[Fast<f32>], out: &mut [i32]) {
+    assert_eq!(input.len(), out.len(), "to_i32: slices must be of equal length");
+    for (o, x) in out.iter_mut().zip(input) {
+        *o = round_ties_even_f32(x.0 * I32_SCALE) as i32;
+    }
+}
+
+/// Convert float samples to `i16` samples, adding `dither()` (in least significant bits)
+/// to each sample before rounding and saturating.
+///
+/// ***Panics*** if the slices are not of equal length.
+pub fn to_i16_dithered(input: &[Fast<f32>], out: &mut [i16],
+                       mut dither: impl FnMut() -> f32)
+{
+    assert_eq!(input.len(), out.len(), "to_i16_dithered: slices must be of equal length");
+    for (o, x) in out.iter_mut().zip(input) {
+        *o = round_ties_even_f32(x.0 * I16_SCALE + dither()) as i16;
+    }
+}
+
+/// Convert float samples to `i32` samples, adding `dither()` (in least significant bits)
+/// to each sample before rounding and saturating.
+///
+/// ***Panics*** if the slices are not of equal length.
+pub fn to_i32_dithered(input: &[Fast<f32>], out: &mut [i32],
+                       mut dither: impl FnMut() -> f32)
+{
+    assert_eq!(input.len(), out.len(), "to_i32_dithered: slices must be of equal length");
+    for (o, x) in out.iter_mut().zip(input) {
+        *o = round_ties_even_f32(x.0 * I32_SCALE + dither()) as i32;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn conversions() {
+        let mut x = [Fast(0f32); 4];
+        from_i32(&[i32::MIN, -1 << 16, 0, 1 << 30], &mut x);
+        assert_eq!(x.map(Fast::get), [-1., -1. / 32768., 0., 0.5]);
+
+        let mut y = [0i32; 4];
+        to_i32(&x, &mut y);
+        assert_eq!(y, [i32::MIN, -1 << 16, 0, 1 << 30]);
+
+        // clipping and rounding
+        let x = [Fast(1f32), Fast(-2.), Fast(0.5 / 32768.), Fast(1.5 / 32768.)];
+        let mut y = [0i16; 4];
+        to_i16(&x, &mut y);
+        assert_eq!(y, [i16::MAX, i16::MIN, 0, 2]);
+        let mut y32 = [0i32; 4];
+        to_i32(&x, &mut y32);
+        assert_eq!(&y32[..2], [i32::MAX, i32::MIN]);
+
+        to_i16_dithered(&x, &mut y, || 0.25);
+        assert_eq!(y, [i16::MAX, i16::MIN, 1, 2]);
+        to_i32_dithered(&x[2..], &mut y32[2..], || 0.);
+        assert_eq!(&y32[2..], [1 << 15, 3 << 15]);
+    }
+}
@@ -0,0 +1,186 @@
+//! Point sets in structure-of-arrays layout.
+//!
+//! [`Points3`] and [`Points3Mut`] view three slices of equal length as the x, y and z
+//! coordinates of a set of points. Each kernel goes through the coordinate slices in
+//! lockstep, which vectorizes without the shuffles that an array of `[x, y, z]` points
+//! needs.
+//!
+//! ```
+//! use fast_floats::points::{Points3, Points3Mut};
+//! use fast_floats::Fast;
+//!
+//! let f = |x| unsafe { Fast::new(x) };
+//! let (mut x, mut y, mut z) = ([f(1.), f(0.)], [f(2.), f(3.)], [f(2.), f(4.)]);
+//! Points3Mut::new(&mut x, &mut y, &mut z).scale([f(2.); 3]);
+//! let mut lengths = [f(0.); 2];
+//! Points3::new(&x, &y, &z).length_each(&mut lengths);
+//! assert_eq!(lengths, [f(6.), f(10.)]);
+//! ```
+
+use crate::{Fast, FastFloat};
+
+/// A view of points whose coordinates are in separate slices.
+#[derive(Copy, Clone, Debug)]
+pub struct Points3<'a, F> {
+    x: &'a [Fast<F>],
+    y: &'a [Fast<F>],
+    z: &'a [Fast<F>],
+}
+
+/// A mutable view of points whose coordinates are in separate slices.
+#[derive(Debug)]
+pub struct Points3Mut<'a, F> {
+    x: &'a mut [Fast<F>],
+    y: &'a mut [Fast<F>],
+    z: &'a mut [Fast<F>],
+}
+
+/// An axis-aligned bounding box.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Aabb<F> {
+    /// The minimum corner
+    pub min: [Fast<F>; 3],
+    /// The maximum corner
+    pub max: [Fast<F>; 3],
+}
+
+fn check_len(x: usize, y: usize, z: usize) {
+    assert!(x == y && x == z, "Points3: coordinate slices must be of equal length");
+}
+
+impl<'a, F> Points3<'a, F> {
+    /// Create a view of the points with coordinates `x`, `y` and `z`.
+    ///
+    /// ***Panics*** if the slices are not of equal length.
+    pub fn new(x: &'a [Fast<F>], y: &'a [Fast<F>], z: &'a [Fast<F>]) -> Self {
+        check_len(x.len(), y.len(), z.len());
+        Points3 { x, y, z }
+    }
+
+    /// Return the number of points
+    pub fn len(&self) -> usize { self.x.len() }
+
+    /// Return `true` if there are no points
+    pub fn is_empty(&self) -> bool { self.x.is_empty() }
+
+    /// Return the x, y and z coordinate slices
+    pub fn coords(&self) -> [&'a [Fast<F>]; 3] { [self.x, self.y, self.z] }
+}
+
+impl<F: FastFloat> Points3<'_, F> {
+    /// Compute the dot product of each point with `v`, `out[i] = p[i] · v`.
+    ///
+    /// ***Panics*** if `out` does not have one element per point.
+    pub fn dot_each(&self, v: [Fast<F>; 3], out: &mut [Fast<F>]) {
+        assert_eq!(out.len(), self.len(), "dot_each: out must have one element per point");
+        for (o, ((&x, &y), &z)) in out.iter_mut().zip(self.x.iter().zip(self.y).zip(self.z)) {
+            *o = x * v[0] + y * v[1] + z * v[2];
+        }
+    }
+
+    /// Compute the length of each point as a vector, `out[i] = |p[i]|`.
+    ///
+    /// ***Panics*** if `out` does not have one element per point.
+    pub fn length_each(&self, out: &mut [Fast<F>]) {
+        assert_eq!(out.len(), self.len(), "length_each: out must have one element per point");
+        for (o, ((&x, &y), &z)) in out.iter_mut().zip(self.x.iter().zip(self.y).zip(self.z)) {
+            *o = Fast((x * x + y * y + z * z).0.sqrt_strict());
+        }
+    }
+
+    /// Return the centroid of the points, their mean, or `None` if there are none
+    pub fn centroid(&self) -> Option<[Fast<F>; 3]> {
+        if self.is_empty() {
+            return None;
+        }
+        let zero = Fast(F::ZERO);
+        let points = self.x.iter().zip(self.y).zip(self.z);
+        let sum = points.fold([zero; 3], |[sx, sy, sz], ((&x, &y), &z)| [sx + x, sy + y, sz + z]);
+        let inv_len = Fast(F::from_f64(1. / self.len() as f64));
+        Some(sum.map(|s| s * inv_len))
+    }
+
+    /// Return the axis-aligned bounding box of the points, or `None` if there are none
+    pub fn aabb(&self) -> Option<Aabb<F>> {
+        let first = [*self.x.first()?, self.y[0], self.z[0]];
+        let points = self.x.iter().zip(self.y).zip(self.z);
+        let (min, max) = points.fold((first, first), |([x0, y0, z0], [x1, y1, z1]), p| {
+            let ((&x, &y), &z) = p;
+            ([x0.min(x), y0.min(y), z0.min(z)], [x1.max(x), y1.max(y), z1.max(z)])
+        });
+        Some(Aabb { min, max })
+    }
+}
+
+impl<'a, F> Points3Mut<'a, F> {
+    /// Create a mutable view of the points with coordinates `x`, `y` and `z`.
+    ///
+    /// ***Panics*** if the slices are not of equal length.
+    pub fn new(x: &'a mut [Fast<F>], y: &'a mut [Fast<F>], z: &'a mut [Fast<F>]) -> Self {
+        check_len(x.len(), y.len(), z.len());
+        Points3Mut { x, y, z }
+    }
+
+    /// Return the number of points
+    pub fn len(&self) -> usize { self.x.len() }
+
+    /// Return `true` if there are no points
+    pub fn is_empty(&self) -> bool { self.x.is_empty() }
+
+    /// Return a shared view of the points
+    pub fn as_points(&self) -> Points3<'_, F> { Points3 { x: self.x, y: self.y, z: self.z } }
+}
+
+impl<F: FastFloat> Points3Mut<'_, F> {
+    /// Add `d` to each point
+    pub fn translate(&mut self, d: [Fast<F>; 3]) {
+        for (xs, d) in [&mut *self.x, &mut *self.y, &mut *self.z].into_iter().zip(d) {
+            for x in xs {
+                *x += d;
+            }
+        }
+    }
+
+    /// Multiply each point by `s`, coordinate by coordinate
+    pub fn scale(&mut self, s: [Fast<F>; 3]) {
+        for (xs, s) in [&mut *self.x, &mut *self.y, &mut *self.z].into_iter().zip(s) {
+            crate::slice::scale(xs, s);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn kernels() {
+        let mut x = [Fast(1f64), Fast(-2.), Fast(-1.)];
+        let mut y = [Fast(2f64), Fast(0.), Fast(3.)];
+        let mut z = [Fast(-3f64), Fast(1.), Fast(9.)];
+        let mut points = Points3Mut::new(&mut x, &mut y, &mut z);
+        points.translate([Fast(1.), Fast(0.), Fast(-1.)]);
+        points.scale([Fast(1.), Fast(2.), Fast(1.)]);
+        assert_eq!(points.len(), 3);
+        let mut out = [Fast(0.); 3];
+        points.as_points().dot_each([Fast(1.), Fast(0.5), Fast(2.)], &mut out);
+        assert_eq!(out, [Fast(-4.), Fast(-1.), Fast(19.)]);
+        points.as_points().length_each(&mut out);
+        assert_eq!(out, [Fast(6.), Fast(1.), Fast(10.)]);
+        assert_eq!(x, [Fast(2.), Fast(-1.), Fast(0.)]);
+    }
+
+    #[test]
+    fn bounds() {
+        let x = [Fast(1f32), Fast(-2.), Fast(4.)];
+        let y = [Fast(0f32), Fast(3.), Fast(3.)];
+        let z = [Fast(-1f32), Fast(-1.), Fast(5.)];
+        let points = Points3::new(&x, &y, &z);
+        assert_eq!(points.centroid(), Some([Fast(1.), Fast(2.), Fast(1.)]));
+        let aabb = Aabb { min: [Fast(-2.), Fast(0.), Fast(-1.)],
+                          max: [Fast(4.), Fast(3.), Fast(5.)] };
+        assert_eq!(points.aabb(), Some(aabb));
+        let empty = Points3::<f32>::new(&[], &[], &[]);
+        assert_eq!((empty.centroid(), empty.aabb()), (None, None));
+    }
+}
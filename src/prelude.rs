@@ -0,0 +1,16 @@
+//! The common types, traits and modules, for a glob import.
+//!
+//! ```
+//! use fast_floats::prelude::*;
+//!
+//! let xs = [1., 2., 3.].map(|x| unsafe { FF64::new(x) });
+//! assert_eq!(slice::sum(&xs), xs.iter().copied().fast_tree_sum());
+//! ```
+
+pub use crate::{Fast, FastFloat, FastMatrix, LogBase, FF32, FF64};
+#[cfg(feature = "alloc")]
+pub use crate::AlignedBuffer;
+pub use crate::accum::TreeSumExt;
+pub use crate::complex::Complex;
+pub use crate::slice::CastFrom;
+pub use crate::{approx, slice};
@@ -0,0 +1,77 @@
+//! The fast-math intrinsics as plain functions, for `f32` and `f64`.
+//!
+//! These are thin wrappers around the `core::intrinsics` functions that `Fast` uses. Code
+//! that wants the intrinsics without the wrapper type can use these names, which stay the
+//! same when the intrinsics are renamed in Rust. With the `algebraic` feature they use the
+//! algebraic intrinsics, like the operators of `Fast`.
+//!
+//! ```
+//! use fast_floats::raw;
+//!
+//! // safe because the arguments and the result are finite
+//! let x = unsafe { raw::fmul_fast(raw::fadd_fast(1., 2.), 4.) };
+//! assert_eq!(x, 12f64);
+//! ```
+//!
+//! # Safety
+//!
+//! All the functions have the same contract: the arguments and the result must be finite,
+//! see [`Fast::new`](crate::Fast::new).
+
+use crate::FastFloat;
+
+/// Return `a + b` with the fast-math flags.
+///
+/// # Safety
+///
+/// The arguments and the result must be finite.
+#[inline(always)]
+pub unsafe fn fadd_fast<F: FastFloat>(a: F, b: F) -> F { F::fadd_fast(a, b) }
+
+/// Return `a - b` with the fast-math flags.
+///
+/// # Safety
+///
+/// The arguments and the result must be finite.
+#[inline(always)]
+pub unsafe fn fsub_fast<F: FastFloat>(a: F, b: F) -> F { F::fsub_fast(a, b) }
+
+/// Return `a * b` with the fast-math flags.
+///
+/// # Safety
+///
+/// The arguments and the result must be finite.
+#[inline(always)]
+pub unsafe fn fmul_fast<F: FastFloat>(a: F, b: F) -> F { F::fmul_fast(a, b) }
+
+/// Return `a / b` with the fast-math flags.
+///
+/// # Safety
+///
+/// The arguments and the result must be finite.
+#[inline(always)]
+pub unsafe fn fdiv_fast<F: FastFloat>(a: F, b: F) -> F { F::fdiv_fast(a, b) }
+
+/// Return `a % b` with the fast-math flags.
+///
+/// # Safety
+///
+/// The arguments and the result must be finite.
+#[inline(always)]
+pub unsafe fn frem_fast<F: FastFloat>(a: F, b: F) -> F { F::frem_fast(a, b) }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ops() {
+        unsafe {
+            assert_eq!(fadd_fast(1f32, 2.), 3.);
+            assert_eq!(fsub_fast(1f64, 2.), -1.);
+            assert_eq!(fmul_fast(3f32, 2.), 6.);
+            assert_eq!(fdiv_fast(1f64, 4.), 0.25);
+            assert_eq!(frem_fast(7f32, 4.), 3.);
+        }
+    }
+}
@@ -0,0 +1,149 @@
+//! Root finding for scalar functions: Newton's method and bisection.
+//!
+//! The iterations compute with `Fast` arithmetic. The functions return a [`Root`], which
+//! tells whether the iteration converged, and how many iterations it took.
+//!
+//! ```
+//! use fast_floats::roots::{self, Root};
+//! use fast_floats::Fast;
+//!
+//! let f = |x| unsafe { Fast::new(x) };
+//! // √2 as the root of x² - 2
+//! let root = roots::newton(|x| x * x - f(2.), |x| x + x, f(1.), f(1e-12), 20);
+//! assert!(matches!(root, Root::Converged { .. }));
+//! assert!((root.value().get() - 2f64.sqrt()).abs() < 1e-12);
+//! ```
+
+use crate::{Fast, FastFloat};
+
+/// The result of a root finding iteration.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Root<F> {
+    /// The iteration converged to `x` within the tolerance
+    Converged {
+        /// The root
+        x: Fast<F>,
+        /// The number of iterations
+        iterations: usize,
+    },
+    /// The iteration stopped at the maximum number of iterations, at `x`
+    MaxIterations {
+        /// The last estimate
+        x: Fast<F>,
+    },
+    /// The iteration could not continue at `x`: for Newton's method the derivative is
+    /// zero, and for bisection the function does not change sign over the interval
+    Failed {
+        /// The last estimate
+        x: Fast<F>,
+    },
+}
+
+impl<F: Copy> Root<F> {
+    /// Return the root, or the last estimate if the iteration did not converge
+    pub fn value(&self) -> Fast<F> {
+        match *self {
+            Root::Converged { x, .. } | Root::MaxIterations { x } | Root::Failed { x } => x,
+        }
+    }
+
+    /// Return the root if the iteration converged
+    pub fn converged(&self) -> Option<Fast<F>> {
+        match *self {
+            Root::Converged { x, .. } => Some(x),
+            _ => None,
+        }
+    }
+}
+
+/// Find a root of `f` with Newton's method, from the initial estimate `x0`.
+///
+/// `df` is the derivative of `f`. The iteration converges when a step is at most `tol`
+/// in magnitude, and fails if the derivative is zero.
+pub fn newton<F: FastFloat>(mut f: impl FnMut(Fast<F>) -> Fast<F>,
+                            mut df: impl FnMut(Fast<F>) -> Fast<F>,
+                            x0: Fast<F>, tol: Fast<F>, max_iter: usize) -> Root<F>
+{
+    let mut x = x0;
+    for i in 0..max_iter {
+        let d = df(x);
+        if d == Fast(F::ZERO) {
+            return Root::Failed { x };
+        }
+        let step = f(x) / d;
+        x -= step;
+        if step.abs() <= tol {
+            return Root::Converged { x, iterations: i + 1 };
+        }
+    }
+    Root::MaxIterations { x }
+}
+
+/// Find a root of `f` in the interval between `a` and `b` by bisection.
+///
+/// `f(a)` and `f(b)` must have opposite signs (or one of them be zero), otherwise the
+/// result is `Failed`. The interval is halved until it is at most `tol` wide, or an exact
+/// root is found; the number of iterations is at most about `log2(|b - a| / tol)`, and at
+/// most the number of bits of the float type when `tol` is zero.
+pub fn bisect<F: FastFloat>(mut f: impl FnMut(Fast<F>) -> Fast<F>, a: Fast<F>, b: Fast<F>,
+                            tol: Fast<F>) -> Root<F>
+{
+    let zero = Fast(F::ZERO);
+    let (mut lo, mut hi) = if a <= b { (a, b) } else { (b, a) };
+    let (flo, fhi) = (f(lo), f(hi));
+    if flo == zero {
+        return Root::Converged { x: lo, iterations: 0 };
+    }
+    if fhi == zero {
+        return Root::Converged { x: hi, iterations: 0 };
+    }
+    if (flo < zero) == (fhi < zero) {
+        return Root::Failed { x: lo.midpoint(hi) };
+    }
+    let lo_negative = flo < zero;
+    let mut iterations = 0;
+    loop {
+        let mid = lo.midpoint(hi);
+        // the interval can not be split further; the width is strict, since it overflows
+        // for brackets wider than the largest float
+        if mid <= lo || mid >= hi || F::sub_strict(hi.0, lo.0) <= tol.0 {
+            return Root::Converged { x: mid, iterations };
+        }
+        iterations += 1;
+        let fmid = f(mid);
+        if fmid == zero {
+            return Root::Converged { x: mid, iterations };
+        }
+        if (fmid < zero) == lo_negative {
+            lo = mid;
+        } else {
+            hi = mid;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn newton_and_bisect() {
+        let f = |x: Fast<f64>| x * x * x - Fast(8.);
+        let df = |x: Fast<f64>| Fast(3.) * x * x;
+        let root = newton(f, df, Fast(3.), Fast(1e-14), 50);
+        assert!((root.converged().unwrap().get() - 2.).abs() < 1e-14);
+        assert_eq!(newton(f, df, Fast(0.), Fast(1e-14), 50), Root::Failed { x: Fast(0.) });
+        assert!(matches!(newton(f, df, Fast(3.), Fast(0.), 2), Root::MaxIterations { .. }));
+
+        let root = bisect(f, Fast(5.), Fast(0.), Fast(1e-10));
+        assert!((root.value().get() - 2.).abs() < 1e-10);
+        let Root::Converged { iterations, .. } = root else { panic!("{:?}", root) };
+        assert!(iterations <= 36);
+        assert!((bisect(f, Fast(0.), Fast(5.), Fast(0.)).value().get() - 2.).abs() < 1e-15);
+        assert_eq!(bisect(f, Fast(1.), Fast(2.), Fast(0.)),
+                   Root::Converged { x: Fast(2.), iterations: 0 });
+        assert!(matches!(bisect(f, Fast(3.), Fast(4.), Fast(0.)), Root::Failed { .. }));
+        let root = bisect(|x| x - Fast(1.), Fast(-f64::MAX), Fast(f64::MAX), Fast(1e-12));
+        assert!((root.value().get() - 1.).abs() < 1e-12, "{:?}", root);
+    }
+}
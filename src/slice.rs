@@ -0,0 +1,949 @@
+//! Slice kernels and helpers for writing them.
+//!
+//! The kernels use a target specific implementation from [`arch`](crate::arch) when
+//! the CPU supports it, and otherwise a portable implementation that relies on the fast-math
+//! flags for vectorization. On x86 and x86_64 support for AVX and FMA is detected at runtime;
+//! on other targets the required target features must be enabled at compile time.
+//!
+//! The implementations associate operations differently, so results can differ in the last
+//! bits between them.
+
+use std::mem::size_of;
+use std::slice;
+
+use crate::eft;
+use crate::{Fast, FastFloat};
+
+/// Return the sum of the elements of `xs`.
+pub fn sum<F: FastFloat>(xs: &[Fast<F>]) -> Fast<F> {
+    F::sum_kernel(xs)
+}
+
+/// Return the dot product of `xs` and `ys`.
+///
+/// ***Panics*** if the slices are not of equal length.
+pub fn dot<F: FastFloat>(xs: &[Fast<F>], ys: &[Fast<F>]) -> Fast<F> {
+    assert_eq!(xs.len(), ys.len(), "dot: slices must be of equal length");
+    F::dot_kernel(xs, ys)
+}
+
+/// Return the weighted dot product `Σ xs[i] * ys[i] * ws[i]`, in one pass.
+///
+/// ***Panics*** if the slices are not of equal length.
+pub fn dot3<F: FastFloat>(xs: &[Fast<F>], ys: &[Fast<F>], ws: &[Fast<F>]) -> Fast<F> {
+    assert_eq!(xs.len(), ys.len(), "dot3: slices must be of equal length");
+    assert_eq!(xs.len(), ws.len(), "dot3: slices must be of equal length");
+    xs.iter().zip(ys).zip(ws).fold(Fast(F::ZERO), |acc, ((&x, &y), &w)| x * y * w + acc)
+}
+
+/// Return the dot product of `xs` and `ys`, computed with an explicit fused multiply-add for
+/// each element.
+///
+/// [`dot`] lets the compiler choose: its products may or may not be contracted with the
+/// additions, and whether they are depends on the target and the kernel that runs. This
+/// function always rounds each multiply-add once, and always associates the same way: the
+/// elements are accumulated in 16 interleaved partial sums, element `i` into sum `i % 16`,
+/// and the partial sums are combined pairwise with regular IEEE additions: sum `i` with sum
+/// `i + 8`, then those results `i` with `i + 4`, and so on.
+///
+/// The speed is the same as [`dot`] where FMA is supported in hardware (detected at runtime
+/// on x86 and x86_64), but much slower where it is not, since each multiply-add is then a
+/// library call.
+///
+/// ***Panics*** if the slices are not of equal length.
+pub fn dot_fma<F: FastFloat>(xs: &[Fast<F>], ys: &[Fast<F>]) -> Fast<F> {
+    assert_eq!(xs.len(), ys.len(), "dot_fma: slices must be of equal length");
+    #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+    if crate::arch::x86::is_avx_fma_supported() {
+        unsafe {
+            return dot_fma_avx(xs, ys);
+        }
+    }
+    dot_fma_impl(xs, ys)
+}
+
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+#[target_feature(enable = "avx,fma")]
+unsafe fn dot_fma_avx<F: FastFloat>(xs: &[Fast<F>], ys: &[Fast<F>]) -> Fast<F> {
+    dot_fma_impl(xs, ys)
+}
+
+#[inline(always)]
+fn dot_fma_impl<F: FastFloat>(xs: &[Fast<F>], ys: &[Fast<F>]) -> Fast<F> {
+    const LANES: usize = 16;
+    let mut acc = [Fast(F::ZERO); LANES];
+    let xb = xs.chunks_exact(LANES);
+    let yb = ys.chunks_exact(LANES);
+    let rest = xb.remainder().iter().zip(yb.remainder());
+    for (xc, yc) in xb.zip(yb) {
+        for ((a, &x), &y) in acc.iter_mut().zip(xc).zip(yc) {
+            *a = x.mul_add(y, *a);
+        }
+    }
+    for (a, (&x, &y)) in acc.iter_mut().zip(rest) {
+        *a = x.mul_add(y, *a);
+    }
+    let mut half = LANES / 2;
+    while half > 0 {
+        for i in 0..half {
+            acc[i] = Fast(F::add_strict(acc[i].0, acc[i + half].0));
+        }
+        half /= 2;
+    }
+    acc[0]
+}
+
+// the number of lanes of the blocked reductions
+const BLOCKED_LANES: usize = 8;
+
+/// Return the sum of the elements of `xs`, associated in a fixed order.
+///
+/// `xs` is split into blocks of `block_size` elements. Within a block, element `i` is added
+/// to lane `i % 8` in sequence, and the eight lanes are combined pairwise:
+/// `((l0 + l4) + (l2 + l6)) + ((l1 + l5) + (l3 + l7))`. The block sums are added in sequence.
+///
+/// The order depends only on the length and `block_size`, so the result is identical on
+/// every target and with every compiler version. See [`TreeSum`](crate::accum::TreeSum) for
+/// why the additions are strict; the lanes are independent, so they still vectorize.
+///
+/// ***Panics*** if `block_size` is zero.
+pub fn sum_blocked<F: FastFloat>(xs: &[Fast<F>], block_size: usize) -> Fast<F> {
+    assert!(block_size != 0, "sum_blocked: block_size must not be zero");
+    xs.chunks(block_size).fold(Fast(F::ZERO), |acc, block| {
+        Fast(F::add_strict(acc.0, blocked_lanes(block.iter().copied())))
+    })
+}
+
+/// Return the dot product of `xs` and `ys`, associated in a fixed order.
+///
+/// The products are summed like in [`sum_blocked`]. Each product is rounded before it is
+/// added, so the result does not depend on whether the target has FMA.
+///
+/// ***Panics*** if the slices are not of equal length, or if `block_size` is zero.
+pub fn dot_blocked<F: FastFloat>(xs: &[Fast<F>], ys: &[Fast<F>], block_size: usize) -> Fast<F> {
+    assert_eq!(xs.len(), ys.len(), "dot_blocked: slices must be of equal length");
+    assert!(block_size != 0, "dot_blocked: block_size must not be zero");
+    xs.chunks(block_size).zip(ys.chunks(block_size)).fold(Fast(F::ZERO), |acc, (xs, ys)| {
+        let products = xs.iter().zip(ys).map(|(x, y)| Fast(F::mul_strict(x.0, y.0)));
+        Fast(F::add_strict(acc.0, blocked_lanes(products)))
+    })
+}
+
+/// Return the dot product of `xs` and `ys`, computed as if in twice the working precision.
+///
+/// This is Dot2 by Ogita, Rump and Oishi: each product and each partial sum is split
+/// into its rounded value and its exact rounding error with the transformations in
+/// [`eft`], and the errors are summed alongside. The result is as accurate as the dot
+/// product rounded from twice the precision, about as if `f32` were computed in `f64`,
+/// unless the dot product is ill-conditioned beyond that precision.
+///
+/// The arithmetic is strict, and sequential: it is an accuracy baseline to compare [`dot`]
+/// with, several times slower. The products use fused multiply-add, which is a library
+/// call where FMA is not supported in hardware.
+///
+/// ***Panics*** if the slices are not of equal length.
+pub fn dot2<F: FastFloat>(xs: &[Fast<F>], ys: &[Fast<F>]) -> Fast<F> {
+    assert_eq!(xs.len(), ys.len(), "dot2: slices must be of equal length");
+    let (mut p, mut s) = (Fast(F::ZERO), F::ZERO);
+    for (&x, &y) in xs.iter().zip(ys) {
+        let (h, r) = eft::two_prod(x, y);
+        let (sum, q) = eft::two_sum(p, h);
+        p = sum;
+        s = F::add_strict(s, F::add_strict(q.0, r.0));
+    }
+    Fast(F::add_strict(p.0, s))
+}
+
+/// Sum `xs` in `BLOCKED_LANES` lanes, and combine the lanes pairwise
+#[inline]
+fn blocked_lanes<F: FastFloat>(xs: impl Iterator<Item = Fast<F>>) -> F {
+    let mut lanes = [F::ZERO; BLOCKED_LANES];
+    for (i, x) in xs.enumerate() {
+        let lane = &mut lanes[i % BLOCKED_LANES];
+        *lane = F::add_strict(*lane, x.0);
+    }
+    let mut n = BLOCKED_LANES;
+    while n > 1 {
+        n /= 2;
+        for i in 0..n {
+            lanes[i] = F::add_strict(lanes[i], lanes[i + n]);
+        }
+    }
+    lanes[0]
+}
+
+/// Return the dot product of a sparse vector, given by `indices` and `values`, with the
+/// dense vector `dense`: `Σ values[k] * dense[indices[k]]`.
+///
+/// The indices are checked against the length of `dense` in a separate pass first, a
+/// maximum that vectorizes, so that the gather loop itself has no bounds checks. The indices
+/// do not need to be sorted or unique. See [`sparse_dot_unchecked`] to skip the check.
+///
+/// ***Panics*** if `indices` and `values` are not of equal length, or if an index is out of
+/// bounds of `dense`.
+pub fn sparse_dot<F: FastFloat>(indices: &[usize], values: &[Fast<F>], dense: &[Fast<F>])
+    -> Fast<F>
+{
+    assert_eq!(indices.len(), values.len(),
+               "sparse_dot: indices and values must be of equal length");
+    let max = indices.iter().fold(0, |m, &i| m.max(i));
+    assert!(indices.is_empty() || max < dense.len(),
+            "sparse_dot: index {} is out of bounds for length {}", max, dense.len());
+    unsafe { sparse_dot_unchecked(indices, values, dense) }
+}
+
+/// Return the dot product of a sparse vector with a dense vector, see [`sparse_dot`],
+/// without checking the indices.
+///
+/// # Safety
+///
+/// `indices` and `values` must be of equal length, and each index must be less than
+/// `dense.len()`.
+pub unsafe fn sparse_dot_unchecked<F: FastFloat>(indices: &[usize], values: &[Fast<F>],
+                                                 dense: &[Fast<F>]) -> Fast<F>
+{
+    debug_assert_eq!(indices.len(), values.len());
+    // independent accumulators hide the latency of the adds
+    let mut acc = [Fast(F::ZERO); 4];
+    let blocks = indices.chunks_exact(4).zip(values.chunks_exact(4));
+    for (is, vs) in blocks {
+        for k in 0..4 {
+            acc[k] += vs[k] * *dense.get_unchecked(is[k]);
+        }
+    }
+    let rest = indices.len() / 4 * 4;
+    let mut sum = (acc[0] + acc[1]) + (acc[2] + acc[3]);
+    for k in rest..indices.len() {
+        sum += *values.get_unchecked(k) * *dense.get_unchecked(*indices.get_unchecked(k));
+    }
+    sum
+}
+
+/// Compute `ys[i] = a * xs[i] + ys[i]` for each element.
+///
+/// ***Panics*** if the slices are not of equal length.
+pub fn axpy<F: FastFloat>(a: Fast<F>, xs: &[Fast<F>], ys: &mut [Fast<F>]) {
+    assert_eq!(xs.len(), ys.len(), "axpy: slices must be of equal length");
+    F::axpy_kernel(a, xs, ys)
+}
+
+/// Compute `xs[i] = a * xs[i]` for each element.
+pub fn scale<F: FastFloat>(xs: &mut [Fast<F>], a: Fast<F>) {
+    map_inplace(xs, |x| a * x)
+}
+
+/// Compute `xs[i] = a * xs[i] + b` for each element.
+///
+/// The multiply-add may be contracted to FMA.
+pub fn scale_add<F: FastFloat>(xs: &mut [Fast<F>], a: Fast<F>, b: Fast<F>) {
+    map_inplace(xs, |x| a * x + b)
+}
+
+/// Compute `dst[i] = a[i] * xs[i] + ys[i]` for each element, in one pass.
+///
+/// The multiply-adds may be contracted to FMA; use [`Fast::mul_add`] in
+/// [`zip_map`]-style loops if fusion must be guaranteed.
+///
+/// ***Panics*** if the slices are not of equal length.
+pub fn fma<F: FastFloat>(dst: &mut [Fast<F>], a: &[Fast<F>], xs: &[Fast<F>], ys: &[Fast<F>]) {
+    let n = dst.len();
+    assert!(a.len() == n && xs.len() == n && ys.len() == n, "fma: slices must be of equal length");
+    for (((d, &a), &x), &y) in dst.iter_mut().zip(a).zip(xs).zip(ys) {
+        *d = a * x + y;
+    }
+}
+
+/// Compute `dst[i] = a * xs[i] + ys[i]` for each element, in one pass.
+///
+/// This is like [`axpy`], but writes the result to `dst`.
+///
+/// ***Panics*** if the slices are not of equal length.
+pub fn fma_scalar<F: FastFloat>(dst: &mut [Fast<F>], a: Fast<F>, xs: &[Fast<F>], ys: &[Fast<F>]) {
+    let n = dst.len();
+    assert!(xs.len() == n && ys.len() == n, "fma_scalar: slices must be of equal length");
+    for ((d, &x), &y) in dst.iter_mut().zip(xs).zip(ys) {
+        *d = a * x + y;
+    }
+}
+
+/// Compute `dst[i] = a[i] * xs[i] + b` for each element, in one pass.
+///
+/// ***Panics*** if the slices are not of equal length.
+pub fn fma_offset<F: FastFloat>(dst: &mut [Fast<F>], a: &[Fast<F>], xs: &[Fast<F>], b: Fast<F>) {
+    let n = dst.len();
+    assert!(a.len() == n && xs.len() == n, "fma_offset: slices must be of equal length");
+    for ((d, &a), &x) in dst.iter_mut().zip(a).zip(xs) {
+        *d = a * x + b;
+    }
+}
+
+/// Return the sum of the squares of the elements of `xs`.
+pub fn sum_of_squares<F: FastFloat>(xs: &[Fast<F>]) -> Fast<F> {
+    F::dot_kernel(xs, xs)
+}
+
+/// Return the mean of the squares of the elements of `xs`, or zero if it is empty.
+pub fn mean_square<F: FastFloat>(xs: &[Fast<F>]) -> Fast<F> {
+    if xs.is_empty() {
+        return Fast(F::ZERO);
+    }
+    sum_of_squares(xs) / F::from_f64(xs.len() as f64)
+}
+
+/// Return the root mean square of the elements of `xs`, or zero if it is empty.
+pub fn rms<F: FastFloat>(xs: &[Fast<F>]) -> Fast<F> {
+    Fast(mean_square(xs).0.sqrt_strict())
+}
+
+/// Return the weighted sum `Σ values[i] * weights[i]`.
+///
+/// ***Panics*** if the slices are not of equal length.
+pub fn weighted_sum<F: FastFloat>(values: &[Fast<F>], weights: &[Fast<F>]) -> Fast<F> {
+    dot(values, weights)
+}
+
+/// Return the weighted mean `Σ values[i] * weights[i] / Σ weights[i]`, or zero if the
+/// weights sum to zero.
+///
+/// Both sums are accumulated in the same pass.
+///
+/// ***Panics*** if the slices are not of equal length.
+pub fn weighted_mean<F: FastFloat>(values: &[Fast<F>], weights: &[Fast<F>]) -> Fast<F> {
+    assert_eq!(values.len(), weights.len(), "weighted_mean: slices must be of equal length");
+    let zero = Fast(F::ZERO);
+    let (sum, total) = values.iter().zip(weights).fold((zero, zero), |(sum, total), (&x, &w)| {
+        (x * w + sum, total + w)
+    });
+    if total == zero { zero } else { sum / total }
+}
+
+/// Fit a line `y = slope x + intercept` to the points `(xs[i], ys[i])` by least squares, and
+/// return `(slope, intercept, r²)`, where `r²` is the coefficient of determination.
+///
+/// The fit is computed in one pass. The points are shifted by the first point before they
+/// are accumulated, which reduces the loss of precision to cancellation.
+///
+/// Return `None` if there are fewer than two points or all `xs` are equal. If all `ys` are
+/// equal, `r²` is one.
+///
+/// ***Panics*** if the slices are not of equal length.
+#[allow(clippy::type_complexity)]
+pub fn linear_fit<F: FastFloat>(xs: &[Fast<F>], ys: &[Fast<F>])
+    -> Option<(Fast<F>, Fast<F>, Fast<F>)>
+{
+    assert_eq!(xs.len(), ys.len(), "linear_fit: slices must be of equal length");
+    let (&x0, &y0) = (xs.first()?, ys.first()?);
+    let zero = Fast(F::ZERO);
+    let (sx, sy, sxx, sxy, syy) = xs.iter().zip(ys).fold((zero, zero, zero, zero, zero),
+        |(sx, sy, sxx, sxy, syy), (&x, &y)| {
+            let (x, y) = (x - x0, y - y0);
+            (sx + x, sy + y, x * x + sxx, x * y + sxy, y * y + syy)
+        });
+    let n = Fast(F::from_f64(xs.len() as f64));
+    let vx = n * sxx - sx * sx;
+    if vx <= zero {
+        return None;
+    }
+    let cov = n * sxy - sx * sy;
+    let vy = n * syy - sy * sy;
+    let slope = cov / vx;
+    let intercept = (sy - slope * sx) / n + y0 - slope * x0;
+    let r2 = if vy <= zero { Fast(F::ONE) } else { cov * cov / (vx * vy) };
+    Some((slope, intercept, r2))
+}
+
+/// Apply the plane rotation `(c, s)` to the pairs `(xs[i], ys[i])`:
+/// `xs[i] = c xs[i] + s ys[i]` and `ys[i] = c ys[i] - s xs[i]`.
+///
+/// ***Panics*** if the slices are not of equal length.
+pub fn rot<F: FastFloat>(xs: &mut [Fast<F>], ys: &mut [Fast<F>], c: Fast<F>, s: Fast<F>) {
+    assert_eq!(xs.len(), ys.len(), "rot: slices must be of equal length");
+    for (x, y) in xs.iter_mut().zip(ys) {
+        let (xi, yi) = (*x, *y);
+        *x = c * xi + s * yi;
+        *y = c * yi - s * xi;
+    }
+}
+
+/// Compute the plane rotation that zeroes `b`: return `(c, s, r)` such that
+/// `c a + s b = r` and `c b - s a = 0`, with `c² + s² = 1`.
+///
+/// As in BLAS `rotg`, `r` has the sign of the larger of `a` and `b` in magnitude, and the
+/// computation is scaled to avoid overflow: `c` and `s` are accurate for all finite inputs,
+/// and `r` is infinite only if its magnitude is beyond the largest float. If both are zero,
+/// the result is `(1, 0, 0)`.
+pub fn rotg<F: FastFloat>(a: Fast<F>, b: Fast<F>) -> (Fast<F>, Fast<F>, Fast<F>) {
+    let zero = Fast(F::ZERO);
+    let scale = a.abs().max(b.abs());
+    if scale == zero {
+        return (Fast(F::ONE), zero, zero);
+    }
+    let (sa, sb) = (a / scale, b / scale);
+    let h = Fast((sa * sa + sb * sb).0.sqrt_strict());
+    let roe = if b.abs() > a.abs() { b } else { a };
+    let h = if roe < zero { -h } else { h };
+    // the only product that can overflow is strict
+    (sa / h, sb / h, Fast(F::mul_strict(scale.0, h.0)))
+}
+
+/// Compute the cumulative sum of `xs` into `out`: `out[i] = xs[0] + … + xs[i]`.
+///
+/// ***Panics*** if the slices are not of equal length.
+pub fn cumsum<F: FastFloat>(xs: &[Fast<F>], out: &mut [Fast<F>]) {
+    assert_eq!(xs.len(), out.len(), "cumsum: slices must be of equal length");
+    let mut acc = Fast(F::ZERO);
+    for (o, &x) in out.iter_mut().zip(xs) {
+        acc += x;
+        *o = acc;
+    }
+}
+
+/// Compute the cumulative product of `xs` into `out`: `out[i] = xs[0] * … * xs[i]`.
+///
+/// The products are not guarded against overflow; see
+/// [`RunningProduct`](crate::accum::RunningProduct) for that.
+///
+/// ***Panics*** if the slices are not of equal length.
+pub fn cumprod<F: FastFloat>(xs: &[Fast<F>], out: &mut [Fast<F>]) {
+    assert_eq!(xs.len(), out.len(), "cumprod: slices must be of equal length");
+    let mut acc = Fast(F::ONE);
+    for (o, &x) in out.iter_mut().zip(xs) {
+        acc *= x;
+        *o = acc;
+    }
+}
+
+/// Replace each element `x` of `xs` with `f(x)`.
+///
+/// For custom element-wise kernels: the loop is a plain pass over the slice, which the
+/// compiler can vectorize if `f` is inlined and free of branches.
+#[inline]
+pub fn map_inplace<F: Copy>(xs: &mut [Fast<F>], mut f: impl FnMut(Fast<F>) -> Fast<F>) {
+    for x in xs {
+        *x = f(*x);
+    }
+}
+
+/// Compute `out[i] = f(a[i], b[i])` for each element.
+///
+/// See [`map_inplace`].
+///
+/// ***Panics*** if the slices are not of equal length.
+#[inline]
+pub fn zip_map<F: Copy>(a: &[Fast<F>], b: &[Fast<F>], out: &mut [Fast<F>],
+                        mut f: impl FnMut(Fast<F>, Fast<F>) -> Fast<F>) {
+    assert_eq!(a.len(), b.len(), "zip_map: slices must be of equal length");
+    assert_eq!(a.len(), out.len(), "zip_map: slices must be of equal length");
+    for ((o, &x), &y) in out.iter_mut().zip(a).zip(b) {
+        *o = f(x, y);
+    }
+}
+
+/// Compute the element-wise minimum `out[i] = min(a[i], b[i])`.
+///
+/// The minimum is a compare and a [`Fast::select`], which vectorizes to compare and blend
+/// instructions. If the elements are equal, such as `-0` and `+0`, the result is `b[i]`.
+///
+/// ***Panics*** if the slices are not of equal length.
+pub fn minimum<F: FastFloat>(a: &[Fast<F>], b: &[Fast<F>], out: &mut [Fast<F>]) {
+    zip_map(a, b, out, |x, y| Fast::select(x < y, x, y))
+}
+
+/// Compute the element-wise maximum `out[i] = max(a[i], b[i])`.
+///
+/// See [`minimum`]; if the elements are equal, the result is `b[i]`.
+///
+/// ***Panics*** if the slices are not of equal length.
+pub fn maximum<F: FastFloat>(a: &[Fast<F>], b: &[Fast<F>], out: &mut [Fast<F>]) {
+    zip_map(a, b, out, |x, y| Fast::select(x > y, x, y))
+}
+
+/// Return the smallest and the largest element of `xs`, in one pass, or `None` if `xs` is
+/// empty.
+///
+/// The elements are compared in eight interleaved lanes, combined at the end, so that the
+/// scan vectorizes.
+pub fn min_max<F: FastFloat>(xs: &[Fast<F>]) -> Option<(Fast<F>, Fast<F>)> {
+    let &first = xs.first()?;
+    let (mut min, mut max) = ([first; 8], [first; 8]);
+    let chunks = xs.chunks_exact(8);
+    let rest = chunks.remainder();
+    for chunk in chunks {
+        for i in 0..8 {
+            min[i] = Fast::select(chunk[i] < min[i], chunk[i], min[i]);
+            max[i] = Fast::select(chunk[i] > max[i], chunk[i], max[i]);
+        }
+    }
+    for (i, &x) in rest.iter().enumerate() {
+        min[i] = Fast::select(x < min[i], x, min[i]);
+        max[i] = Fast::select(x > max[i], x, max[i]);
+    }
+    let min = min.into_iter().reduce(Fast::min).unwrap_or(first);
+    let max = max.into_iter().reduce(Fast::max).unwrap_or(first);
+    Some((min, max))
+}
+
+/// Return the largest magnitude `max |xs[i]|`, or zero if `xs` is empty.
+///
+/// This is computed from [`min_max`].
+pub fn peak<F: FastFloat>(xs: &[Fast<F>]) -> Fast<F> {
+    min_max(xs).map_or(Fast(F::ZERO), |(min, max)| min.abs().max(max.abs()))
+}
+
+/// Compute the element-wise absolute value `out[i] = |xs[i]|`, by clearing the sign bit.
+///
+/// ***Panics*** if the slices are not of equal length.
+pub fn abs<F: FastFloat>(xs: &[Fast<F>], out: &mut [Fast<F>]) {
+    assert_eq!(xs.len(), out.len(), "abs: slices must be of equal length");
+    for (o, x) in out.iter_mut().zip(xs) {
+        *o = x.abs();
+    }
+}
+
+macro_rules! masked_op {
+    ($($name:ident, $op:tt, $doc:expr;)*) => {
+        $(
+        #[doc = $doc]
+        ///
+        /// The operation is computed for every element and the result is chosen with
+        /// [`Fast::select`], so the loop vectorizes to blend instructions.
+        ///
+        /// ***Panics*** if the slices are not of equal length.
+        pub fn $name<F: FastFloat>(dst: &mut [Fast<F>], src: &[Fast<F>], mask: &[bool]) {
+            let msg = concat!(stringify!($name), ": slices must be of equal length");
+            assert_eq!(dst.len(), src.len(), "{}", msg);
+            assert_eq!(dst.len(), mask.len(), "{}", msg);
+            for ((d, &s), &m) in dst.iter_mut().zip(src).zip(mask) {
+                *d = Fast::select(m, *d $op s, *d);
+            }
+        }
+        )*
+    }
+}
+
+masked_op! {
+    add_where, +, "Compute `dst[i] += src[i]` where `mask[i]` is true.";
+    sub_where, -, "Compute `dst[i] -= src[i]` where `mask[i]` is true.";
+    mul_where, *, "Compute `dst[i] *= src[i]` where `mask[i]` is true.";
+}
+
+/// Copy `src[i]` to `dst[i]` where `mask[i]` is true.
+///
+/// ***Panics*** if the slices are not of equal length.
+pub fn copy_where<F: FastFloat>(dst: &mut [Fast<F>], src: &[Fast<F>], mask: &[bool]) {
+    assert_eq!(dst.len(), src.len(), "copy_where: slices must be of equal length");
+    assert_eq!(dst.len(), mask.len(), "copy_where: slices must be of equal length");
+    for ((d, &s), &m) in dst.iter_mut().zip(src).zip(mask) {
+        *d = Fast::select(m, s, *d);
+    }
+}
+
+/// Integer types that floats can be cast to with [`cast_saturating`].
+pub trait CastFrom<F>: Copy {
+    /// Convert `x` with `as`: truncate toward zero and saturate, and map NaN to zero
+    fn cast_from(x: F) -> Self;
+}
+
+macro_rules! impl_cast_from {
+    ($t:ident; $($i:ident)*) => {
+        $(
+        impl CastFrom<$t> for $i {
+            #[inline(always)]
+            fn cast_from(x: $t) -> Self { x as $i }
+        }
+        )*
+    };
+}
+
+impl_cast_from!(f32; i8 i16 i32 i64 u8 u16 u32 u64);
+impl_cast_from!(f64; i8 i16 i32 i64 u8 u16 u32 u64);
+
+/// Cast the floats of `xs` to integers in `out`, like `as`.
+///
+/// Each value is truncated toward zero, values out of the range of the integer type
+/// saturate to its minimum or maximum, and NaN becomes zero. The input is plain floats, so
+/// it can hold infinite and NaN values. For rounding to nearest, convert rounded values,
+/// or see [`pcm`](crate::pcm) and [`ml::quantize`](crate::ml::quantize).
+///
+/// ***Panics*** if the slices are not of equal length.
+///
+/// ```
+/// use fast_floats::slice::cast_saturating;
+///
+/// let mut out = [0i8; 5];
+/// cast_saturating(&[1.9f32, -1.9, 300., f32::NEG_INFINITY, f32::NAN], &mut out);
+/// assert_eq!(out, [1, -1, 127, -128, 0]);
+/// ```
+pub fn cast_saturating<F: Copy, I: CastFrom<F>>(xs: &[F], out: &mut [I]) {
+    assert_eq!(xs.len(), out.len(), "cast_saturating: slices must be of equal length");
+    for (o, &x) in out.iter_mut().zip(xs) {
+        *o = I::cast_from(x);
+    }
+}
+
+/// Sort `xs` in increasing order by [`Fast::total_cmp`].
+///
+/// The sort is stable and may allocate a buffer. Elements that compare equal are identical,
+/// so [`sort_unstable`] gives the same result without allocating.
+#[cfg(feature = "alloc")]
+pub fn sort<F: FastFloat>(xs: &mut [Fast<F>]) {
+    xs.sort_by(Fast::total_cmp)
+}
+
+/// Sort `xs` in increasing order by [`Fast::total_cmp`].
+///
+/// The sort does not allocate.
+pub fn sort_unstable<F: FastFloat>(xs: &mut [Fast<F>]) {
+    xs.sort_unstable_by(Fast::total_cmp)
+}
+
+/// Binary search the sorted slice `xs` for `x`, using [`Fast::total_cmp`].
+///
+/// Return `Ok` with the index of a matching element, or `Err` with the index where `x`
+/// could be inserted to keep `xs` sorted, like `[T]::binary_search`.
+pub fn binary_search<F: FastFloat>(xs: &[Fast<F>], x: Fast<F>) -> Result<usize, usize> {
+    xs.binary_search_by(|y| y.total_cmp(&x))
+}
+
+/// Portable implementations of the kernels.
+///
+/// These are written so that the fast-math flags allow the compiler to vectorize them.
+// (unused when every kernel has a target specific implementation)
+#[allow(dead_code)]
+pub(crate) mod portable {
+    use crate::{Fast, FastFloat};
+
+    pub(crate) fn sum<F: FastFloat>(xs: &[Fast<F>]) -> Fast<F> {
+        xs.iter().fold(Fast(F::ZERO), |acc, &x| acc + x)
+    }
+
+    pub(crate) fn dot<F: FastFloat>(xs: &[Fast<F>], ys: &[Fast<F>]) -> Fast<F> {
+        xs.iter().zip(ys).fold(Fast(F::ZERO), |acc, (&x, &y)| acc + x * y)
+    }
+
+    pub(crate) fn axpy<F: FastFloat>(a: Fast<F>, xs: &[Fast<F>], ys: &mut [Fast<F>]) {
+        for (y, &x) in ys.iter_mut().zip(xs) {
+            *y = a * x + *y;
+        }
+    }
+}
+
+/// Split `xs` into an unaligned head, a body of `N`-element blocks, and a tail.
+///
+/// The body starts at an address aligned to `N * size_of::<F>()` bytes, so for example
+/// blocks of 8 × `f32` are 32-byte aligned, suitable for aligned 256-bit vector loads. The
+/// head is shorter than `N` elements and so is the tail (unless the slice is too short to
+/// contain any aligned block, then everything is in the head).
+///
+/// `N * size_of::<F>()` must be a power of two; this is checked at compile time.
+#[allow(clippy::type_complexity)]
+pub fn split_aligned<F, const N: usize>(xs: &[Fast<F>]) -> (&[Fast<F>], &[[Fast<F>; N]], &[Fast<F>]) {
+    let (head, nblocks) = aligned_parts::<F, N>(xs.as_ptr() as usize, xs.len());
+    let (head, rest) = xs.split_at(head);
+    let (body, tail) = rest.split_at(nblocks * N);
+    // Safety: [Fast<F>; N] has the layout of N consecutive Fast<F>, and body is aligned for it.
+    let body = unsafe { slice::from_raw_parts(body.as_ptr() as *const [Fast<F>; N], nblocks) };
+    (head, body, tail)
+}
+
+/// Split `xs` into an unaligned head, a body of `N`-element blocks, and a tail.
+///
+/// See [`split_aligned`] for details.
+#[allow(clippy::type_complexity)]
+pub fn split_aligned_mut<F, const N: usize>(xs: &mut [Fast<F>])
+    -> (&mut [Fast<F>], &mut [[Fast<F>; N]], &mut [Fast<F>])
+{
+    let (head, nblocks) = aligned_parts::<F, N>(xs.as_ptr() as usize, xs.len());
+    let (head, rest) = xs.split_at_mut(head);
+    let (body, tail) = rest.split_at_mut(nblocks * N);
+    let body = unsafe { slice::from_raw_parts_mut(body.as_mut_ptr() as *mut [Fast<F>; N], nblocks) };
+    (head, body, tail)
+}
+
+/// Fold over `xs`, using `block` for each aligned block of `N` elements (see
+/// [`split_aligned`]) and `scalar` for the elements in the unaligned head and tail.
+///
+/// The parts are visited in order: head, body, tail.
+pub fn fold_aligned<F, A, const N: usize>(xs: &[Fast<F>], init: A,
+                                          mut scalar: impl FnMut(A, Fast<F>) -> A,
+                                          mut block: impl FnMut(A, &[Fast<F>; N]) -> A) -> A
+    where F: Copy,
+{
+    let (head, body, tail) = split_aligned::<F, N>(xs);
+    let mut acc = init;
+    for &x in head {
+        acc = scalar(acc, x);
+    }
+    for b in body {
+        acc = block(acc, b);
+    }
+    for &x in tail {
+        acc = scalar(acc, x);
+    }
+    acc
+}
+
+/// Return (head length, number of blocks) for an aligned split.
+fn aligned_parts<F, const N: usize>(addr: usize, len: usize) -> (usize, usize) {
+    let align = const {
+        assert!((N * size_of::<F>()).is_power_of_two(), "N * size_of::<F>() must be a power of two");
+        N * size_of::<F>()
+    };
+    let misalign = addr % align;
+    let head = if misalign == 0 { 0 } else { (align - misalign) / size_of::<F>() };
+    if head > len {
+        (len, 0)
+    } else {
+        (head, (len - head) / N)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn kernels() {
+        let xs: [Fast<f64>; 37] = core::array::from_fn(|i| Fast(i as f64));
+        let mut ys: [Fast<f64>; 37] = [Fast(1.); 37];
+        assert_eq!(sum(&xs), Fast(666.));
+        assert_eq!(sum::<f64>(&[]), Fast(0.));
+        assert_eq!(dot(&xs, &ys), Fast(666.));
+        axpy(Fast(2.), &xs, &mut ys);
+        assert_eq!(ys[36], Fast(73.));
+        assert_eq!(sum(&ys), Fast(2. * 666. + 37.));
+        assert_eq!(dot3(&xs, &ys, &xs), Fast(903318.));
+        scale_add(&mut ys, Fast(0.5), Fast(-0.5));
+        assert_eq!(ys[36], Fast(36.));
+        scale(&mut ys, Fast(-2.));
+        assert_eq!(ys[36], Fast(-72.));
+    }
+
+    #[test]
+    fn fused_dot() {
+        let xs: [Fast<f32>; 37] = core::array::from_fn(|i| Fast(i as f32));
+        assert_eq!(dot_fma(&xs, &xs), dot(&xs, &xs));
+        // lane 0 computes fma(x, x, -x²) for the rounded x², which is not zero
+        let x = Fast(1. + 2f64.powi(-30));
+        let (mut xs, mut ys) = ([Fast(0.); 17], [Fast(0.); 17]);
+        (xs[0], ys[0]) = (-(x * x), Fast(1.));
+        (xs[16], ys[16]) = (x, x);
+        assert_eq!(dot_fma(&xs, &ys), Fast(2f64.powi(-60)));
+        // the lanes are combined pairwise, (2⁵³ + 1) + (1 + 1) = 2⁵³ + 2, while a sequential
+        // sum of the lanes would round away each of the ones
+        let mut xs = [Fast(0f64); 16];
+        (xs[0], xs[4], xs[8], xs[12]) = (Fast(2f64.powi(53)), Fast(1.), Fast(1.), Fast(1.));
+        assert_eq!(dot_fma(&xs, &[Fast(1.); 16]), Fast(2f64.powi(53) + 2.));
+    }
+
+    #[test]
+    fn squares() {
+        let xs = [Fast(1.), Fast(-1.), Fast(3.), Fast(-3.)];
+        assert_eq!(sum_of_squares(&xs), Fast(20.));
+        assert_eq!(mean_square(&xs), Fast(5.));
+        assert_eq!(rms(&xs[2..]), Fast(3.));
+        assert_eq!(rms::<f32>(&[]), Fast(0.));
+    }
+
+    #[test]
+    fn weighted() {
+        let xs = [Fast(1.), Fast(2.), Fast(4.)];
+        let ws = [Fast(1.), Fast(0.), Fast(3.)];
+        assert_eq!(weighted_sum(&xs, &ws), Fast(13.));
+        assert_eq!(weighted_mean(&xs, &ws), Fast(3.25));
+        assert_eq!(weighted_mean(&xs, &[Fast(0.); 3]), Fast(0.));
+    }
+
+    fn assert_close(xs: &[Fast<f64>], ys: &[f64]) {
+        for (x, y) in xs.iter().zip(ys) {
+            assert!((x.get() - y).abs() < 1e-12, "{:?} != {:?}", xs, ys);
+        }
+    }
+
+    #[test]
+    fn fit() {
+        let xs = [Fast(1e6 + 1.), Fast(1e6 + 2.), Fast(1e6 + 3.), Fast(1e6 + 4.)];
+        let ys = xs.map(|x| x * 2. - 1.);
+        let (slope, intercept, r2) = linear_fit(&xs, &ys).unwrap();
+        assert_close(&[slope, intercept, r2], &[2., -1., 1.]);
+        let (_, _, r2) = linear_fit(&xs, &[Fast(1.), Fast(0.), Fast(0.), Fast(1.)]).unwrap();
+        assert_eq!(r2, Fast(0.));
+        assert_eq!(linear_fit(&xs[..1], &ys[..1]), None);
+        assert_eq!(linear_fit::<f32>(&[], &[]), None);
+    }
+
+    #[test]
+    fn rotation() {
+        let (c, s, r) = rotg(Fast(3.), Fast(-4.));
+        assert_close(&[c, s, r], &[-0.6, 0.8, -5.]);
+        let mut xs = [Fast(3.), Fast(1.)];
+        let mut ys = [Fast(-4.), Fast(0.)];
+        rot(&mut xs, &mut ys, c, s);
+        assert_close(&xs, &[-5., -0.6]);
+        assert_close(&ys, &[0., -0.8]);
+        assert_eq!(rotg(Fast(0.), Fast(0.)), (Fast(1.), Fast(0.), Fast(0.)));
+        // |a| + |b| overflows, r does not
+        let (c, s, r) = rotg(Fast(0.6 * f64::MAX), Fast(0.6 * f64::MAX));
+        assert_close(&[c, s, r / f64::MAX], &[0.5f64.sqrt(), 0.5f64.sqrt(), 0.72f64.sqrt()]);
+        let (c, s, r) = rotg(Fast(f64::MAX), Fast(-f64::MAX));
+        assert_close(&[c, s], &[0.5f64.sqrt(), -(0.5f64.sqrt())]);
+        assert_eq!(r.get(), f64::INFINITY);
+    }
+
+    #[test]
+    fn blocked() {
+        let xs: [Fast<f64>; 21] = std::array::from_fn(|i| Fast(1. / (i + 1) as f64));
+        // block of 10: lanes 0..8 get 1, 2 elements; the lanes are combined pairwise
+        let block = |xs: &[Fast<f64>]| {
+            let mut lanes = [0.; 8];
+            for (i, x) in xs.iter().enumerate() {
+                lanes[i % 8] += x.get();
+            }
+            ((lanes[0] + lanes[4]) + (lanes[2] + lanes[6])) +
+                ((lanes[1] + lanes[5]) + (lanes[3] + lanes[7]))
+        };
+        let expected = ((0. + block(&xs[..10])) + block(&xs[10..20])) + block(&xs[20..]);
+        assert_eq!(sum_blocked(&xs, 10).get(), expected);
+        assert_eq!(dot_blocked(&xs, &[Fast(1.); 21], 10).get(), expected);
+        assert_eq!(sum_blocked::<f32>(&[], 4), Fast(0.));
+    }
+
+    #[test]
+    fn compensated() {
+        // 4097² = 2²⁴ + 2¹³ + 1 is not representable in f32; its rounding error remains
+        let xs = [Fast(4097f32), Fast(-16785408.), Fast(1.)];
+        let ys = [Fast(4097f32), Fast(1.), Fast(0.5)];
+        assert_eq!(dot2(&xs, &ys), Fast(1.5));
+        assert_eq!(dot2::<f64>(&[], &[]), Fast(0.));
+    }
+
+    #[test]
+    fn extrema() {
+        let xs: [Fast<f64>; 19] = std::array::from_fn(|i| Fast((i as f64 - 6.) * (i % 3) as f64));
+        assert_eq!(min_max(&xs), Some((Fast(-8.), Fast(22.))));
+        assert_eq!(min_max(&xs[..2]), Some((Fast(-5.), Fast(0.))));
+        assert_eq!(peak(&xs[..11]), Fast(8.));
+        assert_eq!(min_max::<f32>(&[]), None);
+        assert_eq!(peak::<f32>(&[]), Fast(0.));
+    }
+
+    #[test]
+    fn elementwise() {
+        let a = [Fast(1f32), Fast(-2.), Fast(0.)];
+        let b = [Fast(0f32), Fast(3.), Fast(-0.)];
+        let mut out = [Fast(0.); 3];
+        minimum(&a, &b, &mut out);
+        assert_eq!(out.map(Fast::to_bits), [0., -2., -0.].map(f32::to_bits));
+        maximum(&a, &b, &mut out);
+        assert_eq!(out.map(Fast::to_bits), [1., 3., -0.].map(f32::to_bits));
+        abs(&a, &mut out);
+        assert_eq!(out, [Fast(1.), Fast(2.), Fast(0.)]);
+    }
+
+    #[test]
+    fn sparse() {
+        let dense: [Fast<f64>; 8] = std::array::from_fn(|i| Fast(i as f64));
+        let indices = [7, 1, 1, 3, 0, 6];
+        let values = [Fast(1.), Fast(2.), Fast(3.), Fast(4.), Fast(5.), Fast(0.5)];
+        assert_eq!(sparse_dot(&indices, &values, &dense), Fast(7. + 2. + 3. + 12. + 3.));
+        assert_eq!(sparse_dot(&[], &[], &dense[..0]), Fast(0.));
+    }
+
+    #[test]
+    fn masked() {
+        let mask = [true, false, true];
+        let src = [Fast(2f64), Fast(3.), Fast(4.)];
+        let mut dst = [Fast(1.); 3];
+        add_where(&mut dst, &src, &mask);
+        assert_eq!(dst, [Fast(3.), Fast(1.), Fast(5.)]);
+        mul_where(&mut dst, &src, &mask);
+        assert_eq!(dst, [Fast(6.), Fast(1.), Fast(20.)]);
+        sub_where(&mut dst, &src, &[false, true, false]);
+        assert_eq!(dst, [Fast(6.), Fast(-2.), Fast(20.)]);
+        copy_where(&mut dst, &src, &[false, false, true]);
+        assert_eq!(dst, [Fast(6.), Fast(-2.), Fast(4.)]);
+    }
+
+    #[test]
+    fn casts() {
+        let xs = [-1e10, -2.5, 0.9, 70000., f64::INFINITY, f64::NAN];
+        let mut out = [0i32; 6];
+        cast_saturating(&xs, &mut out);
+        assert_eq!(out, [i32::MIN, -2, 0, 70000, i32::MAX, 0]);
+        let mut out = [0u16; 6];
+        cast_saturating(&xs, &mut out);
+        assert_eq!(out, [0, 0, 0, u16::MAX, u16::MAX, 0]);
+    }
+
+    #[test]
+    fn cumulative() {
+        let mut out = [Fast(0.); 4];
+        cumprod(&[Fast(1.), Fast(2.), Fast(3.), Fast(4.)], &mut out);
+        assert_eq!(out, [Fast(1.), Fast(2.), Fast(6.), Fast(24.)]);
+        cumsum(&[Fast(1.), Fast(2.), Fast(3.), Fast(4.)], &mut out);
+        assert_eq!(out, [Fast(1.), Fast(3.), Fast(6.), Fast(10.)]);
+    }
+
+    #[test]
+    fn ternary() {
+        let xs = [Fast(1.), Fast(2.), Fast(3.)];
+        let mut out = [Fast(0.); 3];
+        fma(&mut out, &xs, &xs, &xs);
+        assert_eq!(out, [Fast(2.), Fast(6.), Fast(12.)]);
+        fma_scalar(&mut out, Fast(-1.), &xs, &xs);
+        assert_eq!(out, [Fast(0.); 3]);
+        fma_offset(&mut out, &xs, &xs, Fast(1.));
+        assert_eq!(out, [Fast(2.), Fast(5.), Fast(10.)]);
+    }
+
+    #[test]
+    fn maps() {
+        let mut xs = [Fast(1.), Fast(2.), Fast(3.)];
+        map_inplace(&mut xs, |x| x * x + 1.);
+        assert_eq!(xs, [Fast(2.), Fast(5.), Fast(10.)]);
+        let mut out = [Fast(0.); 3];
+        zip_map(&xs, &[Fast(1.), Fast(0.), Fast(-1.)], &mut out, |x, y| x.max(y));
+        assert_eq!(out, xs);
+    }
+
+    #[test]
+    fn sorting() {
+        let mut xs = [Fast(3f64), Fast(-0.), Fast(-1.), Fast(0.), Fast(2.)];
+        sort_unstable(&mut xs);
+        assert_eq!(xs.map(|x| x.get().to_bits()),
+                   [-1., -0., 0., 2., 3.].map(f64::to_bits));
+        assert_eq!(binary_search(&xs, Fast(2.)), Ok(3));
+        assert_eq!(binary_search(&xs, Fast(1.)), Err(3));
+        assert_eq!(binary_search(&xs, Fast(-0.)), Ok(1));
+        #[cfg(feature = "alloc")]
+        {
+            let mut ys = [Fast(2.), Fast(-0.), Fast(0.), Fast(-1.), Fast(3.)];
+            sort(&mut ys);
+            assert_eq!(ys, xs);
+        }
+    }
+
+    #[test]
+    fn split() {
+        let data: [Fast<f32>; 40] = core::array::from_fn(|i| Fast(i as f32));
+        for start in 0..10 {
+            for end in start..data.len() {
+                let xs = &data[start..end];
+                let (head, body, tail) = split_aligned::<_, 8>(xs);
+                assert_eq!(head.len() + body.len() * 8 + tail.len(), xs.len());
+                if !body.is_empty() {
+                    assert_eq!(body.as_ptr() as usize % 32, 0);
+                    assert!(head.len() < 8 && tail.len() < 8);
+                }
+                let visited = fold_aligned::<_, _, 8>(xs, 0, |n, x| {
+                    assert_eq!(x, xs[n]);
+                    n + 1
+                }, |n, b| {
+                    assert_eq!(&b[..], &xs[n..n + 8]);
+                    n + 8
+                });
+                assert_eq!(visited, xs.len());
+            }
+        }
+    }
+}
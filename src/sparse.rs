@@ -0,0 +1,96 @@
+//! Sparse matrices in compressed sparse row (CSR) format.
+//!
+//! ```
+//! use fast_floats::sparse::CsrMatrix;
+//! use fast_floats::Fast;
+//!
+//! let f = |x| unsafe { Fast::new(x) };
+//! // [[1, 0, 2],
+//! //  [0, 0, 3]]
+//! let values = [1., 2., 3.].map(f);
+//! let a = CsrMatrix::new(3, &[0, 2, 3], &[0, 2, 2], &values);
+//! let mut y = [f(0.); 2];
+//! a.spmv(&[1., 1., 2.].map(f), &mut y);
+//! assert_eq!(y, [5., 6.].map(f));
+//! ```
+
+use crate::{Fast, FastFloat};
+
+/// A borrowed sparse matrix in compressed sparse row (CSR) format.
+///
+/// The nonzero elements of row `i` are `values[row_ptr[i]..row_ptr[i + 1]]`, in the
+/// columns `col_idx[row_ptr[i]..row_ptr[i + 1]]`. The structure is checked when the matrix
+/// is created, so the kernels do not need bounds checks.
+#[derive(Copy, Clone, Debug)]
+pub struct CsrMatrix<'a, F> {
+    cols: usize,
+    row_ptr: &'a [usize],
+    col_idx: &'a [usize],
+    values: &'a [Fast<F>],
+}
+
+impl<'a, F> CsrMatrix<'a, F> {
+    /// Create a matrix with `cols` columns and `row_ptr.len() - 1` rows.
+    ///
+    /// ***Panics*** if `row_ptr` is empty, does not start with zero, or decreases; if
+    /// its last element is not the length of `col_idx` and `values`; or if a column index is
+    /// not less than `cols`.
+    pub fn new(cols: usize, row_ptr: &'a [usize], col_idx: &'a [usize], values: &'a [Fast<F>])
+        -> Self
+    {
+        assert!(row_ptr.first() == Some(&0), "CsrMatrix: row_ptr must start with zero");
+        assert!(row_ptr.windows(2).all(|w| w[0] <= w[1]), "CsrMatrix: row_ptr must not decrease");
+        let nnz = row_ptr[row_ptr.len() - 1];
+        assert!(col_idx.len() == nnz && values.len() == nnz,
+                "CsrMatrix: col_idx and values must have {} elements", nnz);
+        assert!(col_idx.iter().all(|&j| j < cols), "CsrMatrix: column index out of bounds");
+        CsrMatrix { cols, row_ptr, col_idx, values }
+    }
+
+    /// Return the number of rows
+    pub fn rows(&self) -> usize { self.row_ptr.len() - 1 }
+
+    /// Return the number of columns
+    pub fn cols(&self) -> usize { self.cols }
+
+    /// Return the number of stored elements
+    pub fn nnz(&self) -> usize { self.values.len() }
+}
+
+impl<F: FastFloat> CsrMatrix<'_, F> {
+    /// Compute the matrix-vector product `y = A x`.
+    ///
+    /// Each row is accumulated with [`Fast::mul_add`].
+    ///
+    /// ***Panics*** if `x` does not have `cols` elements or `y` does not have `rows`
+    /// elements.
+    pub fn spmv(&self, x: &[Fast<F>], y: &mut [Fast<F>]) {
+        assert_eq!(x.len(), self.cols, "CsrMatrix::spmv: x must have one element per column");
+        assert_eq!(y.len(), self.rows(), "CsrMatrix::spmv: y must have one element per row");
+        for (yi, w) in y.iter_mut().zip(self.row_ptr.windows(2)) {
+            let (cols, values) = (&self.col_idx[w[0]..w[1]], &self.values[w[0]..w[1]]);
+            *yi = cols.iter().zip(values).fold(Fast(F::ZERO), |acc, (&j, &v)| {
+                // the column indices were checked in `new`
+                v.mul_add(unsafe { *x.get_unchecked(j) }, acc)
+            });
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn spmv() {
+        // [[0, 0, 0, 0],
+        //  [1, 0, 2, 0],
+        //  [0, 3, 0, 4]]
+        let values = [Fast(1f64), Fast(2.), Fast(3.), Fast(4.)];
+        let a = CsrMatrix::new(4, &[0, 0, 2, 4], &[0, 2, 1, 3], &values);
+        assert_eq!((a.rows(), a.cols(), a.nnz()), (3, 4, 4));
+        let mut y = [Fast(-1.); 3];
+        a.spmv(&[Fast(1.), Fast(2.), Fast(3.), Fast(4.)], &mut y);
+        assert_eq!(y, [Fast(0.), Fast(7.), Fast(22.)]);
+    }
+}
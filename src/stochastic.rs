@@ -0,0 +1,202 @@
+//! Stochastic arithmetic, to estimate how many digits of a result are significant.
+//!
+//! This is the CESTAC method, as in the CADNA library: a [`Stochastic`] value computes `N`
+//! samples of the same computation, where each operation rounds each result up or down at
+//! random instead of to nearest. Digits that are lost to rounding errors differ between the
+//! samples, and [`Stochastic::significant_digits`] estimates, with 95% confidence, how many
+//! digits the samples have in common.
+//!
+//! Running a fast-math kernel on `Stochastic` values is not possible, but running a strict
+//! version of the same algorithm shows how much the result depends on the rounding of each
+//! operation, and so how much room there is for the reassociations of fast-math.
+//!
+//! The random choices come from a hash of each result and its sample index, so a
+//! computation is reproducible.
+//!
+//! ```
+//! use fast_floats::stochastic::Stochastic;
+//! use fast_floats::Fast;
+//!
+//! let f = |x| Stochastic::<f64>::new(unsafe { Fast::new(x) });
+//! let mut sum = f(0.);
+//! for _ in 0..1000 {
+//!     sum += f(0.1);
+//! }
+//! assert!(sum.significant_digits() > 12.);
+//! // 1 + 1e-15 - 1 has lost most digits to cancellation
+//! let x = f(1.) + f(1e-15) - f(1.);
+//! assert!(x.significant_digits() < 3.);
+//! ```
+
+use std::ops::{Add, AddAssign, Div, DivAssign, Mul, MulAssign, Neg, Sub, SubAssign};
+
+use crate::eft;
+use crate::float::sealed::Sealed;
+use crate::{Fast, FastFloat};
+
+/// A value computed in `N` samples with random rounding, see the module documentation.
+///
+/// `N` must be at least two; three is the usual choice.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Stochastic<F, const N: usize = 3> {
+    samples: [F; N],
+}
+
+impl<F: FastFloat, const N: usize> Stochastic<F, N> {
+    /// Create a value whose samples are all `x`, which is taken to be exact
+    pub fn new(x: Fast<F>) -> Self {
+        const { assert!(N >= 2, "Stochastic: N must be at least two") };
+        Stochastic { samples: [x.0; N] }
+    }
+
+    /// Return the samples
+    pub fn samples(&self) -> [F; N] { self.samples }
+
+    /// Return the mean of the samples, which is the result of the computation
+    pub fn mean(&self) -> F {
+        let sum = self.samples.iter().fold(0., |acc, x| acc + x.to_f64());
+        F::from_f64(sum / N as f64)
+    }
+
+    /// Return the estimated number of significant decimal digits of the mean.
+    ///
+    /// The estimate is `log₁₀(√N |mean| / (τ σ))`, where `σ` is the standard deviation of
+    /// the samples and `τ` is the 95% quantile of Student's t-distribution with `N - 1`
+    /// degrees of freedom. It is clamped to the range from zero to the precision of `F`,
+    /// which is also the result if all samples are equal.
+    pub fn significant_digits(&self) -> f64 {
+        let ln10 = core::f64::consts::LN_10;
+        let precision = -Sealed::ln(F::EPSILON.to_f64() / 2.) / ln10;
+        let mean = self.samples.iter().fold(0., |acc, x| acc + x.to_f64()) / N as f64;
+        let var = self.samples.iter().fold(0., |acc, x| {
+            let d = x.to_f64() - mean;
+            acc + d * d
+        }) / (N - 1) as f64;
+        if var == 0. {
+            return precision;
+        }
+        let tau = STUDENT_T95.get(N - 2).copied().unwrap_or(1.96);
+        let ratio = Sealed::sqrt(N as f64 / var) * mean.abs() / tau;
+        if ratio <= 1. {
+            return 0.;
+        }
+        (Sealed::ln(ratio) / ln10).min(precision)
+    }
+
+    /// Return the square root
+    pub fn sqrt(self) -> Self {
+        self.map(|_, a| {
+            let q = a.sqrt_strict();
+            // sqrt(a) = q + r / 2q to first order, where r = a - q²
+            (q, (-q).mul_add(q, a))
+        })
+    }
+
+    /// Compute each sample with `op`, which returns the result rounded to nearest and a
+    /// value with the sign of its rounding error, and round it at random
+    #[inline]
+    fn map(self, mut op: impl FnMut(usize, F) -> (F, F)) -> Self {
+        let mut samples = self.samples;
+        for (i, x) in samples.iter_mut().enumerate() {
+            let (r, error) = op(i, *x);
+            *x = random_rounding(r, error, i);
+        }
+        Stochastic { samples }
+    }
+}
+
+/// The 95% quantiles of Student's t-distribution with 1 to 9 degrees of freedom
+const STUDENT_T95: [f64; 9] = [12.706, 4.303, 3.182, 2.776, 2.571, 2.447, 2.365, 2.306, 2.262];
+
+/// Return `r` or its neighbour in the direction of `error`, chosen by a hash of `r` and
+/// the sample `index`
+#[inline]
+fn random_rounding<F: FastFloat>(r: F, error: F, index: usize) -> F {
+    if error == F::ZERO || !r.is_finite() {
+        return r;
+    }
+    // splitmix64 finalizer
+    let mut z = r.to_f64().to_bits() ^ (index as u64 + 1).wrapping_mul(0x9e37_79b9_7f4a_7c15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    if (z ^ (z >> 31)) & 1 == 0 {
+        r
+    } else if error > F::ZERO {
+        r.next_up()
+    } else {
+        r.next_down()
+    }
+}
+
+macro_rules! impl_op {
+    ($($tr:ident, $method:ident, $assign_tr:ident, $assign:ident,
+       |$a:ident, $b:ident| $body:expr;)*) => {
+        $(
+        impl<F: FastFloat, const N: usize> $tr for Stochastic<F, N> {
+            type Output = Self;
+            #[inline]
+            fn $method(self, rhs: Self) -> Self {
+                self.map(|i, $a| {
+                    let $b = rhs.samples[i];
+                    $body
+                })
+            }
+        }
+
+        impl<F: FastFloat, const N: usize> $assign_tr for Stochastic<F, N> {
+            #[inline]
+            fn $assign(&mut self, rhs: Self) { *self = self.$method(rhs); }
+        }
+        )*
+    }
+}
+
+impl_op! {
+    Add, add, AddAssign, add_assign, |a, b| {
+        let (s, e) = eft::two_sum(Fast(a), Fast(b));
+        (s.0, e.0)
+    };
+    Sub, sub, SubAssign, sub_assign, |a, b| {
+        let (s, e) = eft::two_sum(Fast(a), Fast(-b));
+        (s.0, e.0)
+    };
+    Mul, mul, MulAssign, mul_assign, |a, b| {
+        let (p, e) = eft::two_prod(Fast(a), Fast(b));
+        (p.0, e.0)
+    };
+    Div, div, DivAssign, div_assign, |a, b| {
+        let q = F::div_strict(a, b);
+        // a / b = q + r / b, where r = a - q b
+        let r = (-q).mul_add(b, a);
+        (q, if b > F::ZERO { r } else { -r })
+    };
+}
+
+impl<F: FastFloat, const N: usize> Neg for Stochastic<F, N> {
+    type Output = Self;
+    #[inline]
+    fn neg(self) -> Self { Stochastic { samples: self.samples.map(|x| -x) } }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn digits() {
+        let f = |x| Stochastic::<f32, 4>::new(Fast(x));
+        // exact operations are not perturbed
+        let x = f(1.5) * f(2.) + f(1.) - f(0.5) / f(4.);
+        assert_eq!(x.samples(), [3.875; 4]);
+        assert!((x.significant_digits() - 7.22).abs() < 0.01);
+
+        let third = f(1.) / f(3.);
+        assert!(third.samples().iter().all(|&x| x == 1. / 3. || x == (1f32 / 3.).next_down()));
+        let s = 2f32.sqrt();
+        assert!(f(2.).sqrt().samples().iter().all(|&x| x == s || x == s.next_up()));
+
+        // the rounding error of 1 + 1e-6 is large relative to 1e-6
+        let y = f(1.) + f(1e-6) - f(1.);
+        assert!(y.significant_digits() < 3., "{:?}", y);
+    }
+}
@@ -0,0 +1,225 @@
+//! Strided views of slices, such as the columns of a row-major matrix.
+//!
+//! [`Strided`] and [`StridedMut`] view every `stride`th element of a slice, without copying.
+//! Their reductions accumulate with the fast-math flags, like the kernels in
+//! [`slice`](crate::slice), and use those kernels directly when the stride is one.
+//!
+//! ```
+//! use fast_floats::strided::{Strided, StridedMut};
+//! use fast_floats::Fast;
+//!
+//! let f = |x| unsafe { Fast::new(x) };
+//! // a 3 × 2 row-major matrix
+//! let mut m = [1., 2., 3., 4., 5., 6.].map(f);
+//! assert_eq!(Strided::new(&m[1..], 2).sum(), f(12.));
+//!
+//! // add 10 times the first column to the second column
+//! let first = m;
+//! StridedMut::new(&mut m[1..], 2).axpy(f(10.), Strided::new(&first, 2));
+//! assert_eq!(m, [1., 12., 3., 34., 5., 56.].map(f));
+//! ```
+
+use std::marker::PhantomData;
+use std::slice;
+
+use crate::{Fast, FastFloat};
+
+/// A view of the elements `0, stride, 2 stride, ...` of a slice.
+#[derive(Copy, Clone, Debug)]
+pub struct Strided<'a, F> {
+    ptr: *const Fast<F>,
+    len: usize,
+    stride: usize,
+    life: PhantomData<&'a [Fast<F>]>,
+}
+
+/// A mutable view of the elements `0, stride, 2 stride, ...` of a slice.
+#[derive(Debug)]
+pub struct StridedMut<'a, F> {
+    ptr: *mut Fast<F>,
+    len: usize,
+    stride: usize,
+    life: PhantomData<&'a mut [Fast<F>]>,
+}
+
+// like &[Fast<F>] and &mut [Fast<F>]
+unsafe impl<F: Sync> Send for Strided<'_, F> {}
+unsafe impl<F: Sync> Sync for Strided<'_, F> {}
+unsafe impl<F: Send> Send for StridedMut<'_, F> {}
+unsafe impl<F: Sync> Sync for StridedMut<'_, F> {}
+
+fn strided_len(len: usize, stride: usize) -> usize {
+    assert!(stride != 0, "Strided: stride must not be zero");
+    len.div_ceil(stride)
+}
+
+impl<'a, F> Strided<'a, F> {
+    /// Create a view of every `stride`th element of `xs`, starting with the first.
+    ///
+    /// ***Panics*** if `stride` is zero.
+    pub fn new(xs: &'a [Fast<F>], stride: usize) -> Self {
+        let len = strided_len(xs.len(), stride);
+        Strided { ptr: xs.as_ptr(), len, stride, life: PhantomData }
+    }
+
+    /// Create a view from a pointer, a length and a stride.
+    ///
+    /// # Safety
+    ///
+    /// `ptr.add(i * stride)` must be valid for reads for each `i < len`, and the elements
+    /// must not be mutated during the lifetime `'a`.
+    pub unsafe fn from_raw_parts(ptr: *const Fast<F>, len: usize, stride: usize) -> Self {
+        Strided { ptr, len, stride, life: PhantomData }
+    }
+
+    /// Return the number of elements in the view
+    pub fn len(&self) -> usize { self.len }
+
+    /// Return `true` if the view has no elements
+    pub fn is_empty(&self) -> bool { self.len == 0 }
+
+    /// Return the stride of the view, in elements
+    pub fn stride(&self) -> usize { self.stride }
+
+    /// Return the element at `index`, or `None` if it is out of bounds
+    pub fn get(&self, index: usize) -> Option<&'a Fast<F>> {
+        if index < self.len {
+            unsafe { Some(&*self.ptr.add(index * self.stride)) }
+        } else {
+            None
+        }
+    }
+
+    /// Return an iterator over the elements
+    pub fn iter(&self) -> impl ExactSizeIterator<Item = &'a Fast<F>> + 'a {
+        let (ptr, stride) = (self.ptr, self.stride);
+        (0..self.len).map(move |i| unsafe { &*ptr.add(i * stride) })
+    }
+
+    /// Return the view as a slice, if the stride is one
+    fn as_contiguous(&self) -> Option<&'a [Fast<F>]> {
+        if self.stride == 1 {
+            unsafe { Some(slice::from_raw_parts(self.ptr, self.len)) }
+        } else {
+            None
+        }
+    }
+}
+
+impl<'a, F: FastFloat> Strided<'a, F> {
+    /// Return the sum of the elements
+    pub fn sum(&self) -> Fast<F> {
+        if let Some(xs) = self.as_contiguous() {
+            return crate::slice::sum(xs);
+        }
+        self.iter().fold(Fast(F::ZERO), |acc, &x| acc + x)
+    }
+
+    /// Return the dot product of the view with `other`.
+    ///
+    /// ***Panics*** if the views are not of equal length.
+    pub fn dot(&self, other: Strided<'_, F>) -> Fast<F> {
+        assert_eq!(self.len, other.len, "Strided::dot: views must be of equal length");
+        if let (Some(xs), Some(ys)) = (self.as_contiguous(), other.as_contiguous()) {
+            return crate::slice::dot(xs, ys);
+        }
+        self.iter().zip(other.iter()).fold(Fast(F::ZERO), |acc, (&x, &y)| x * y + acc)
+    }
+}
+
+impl<'a, F> From<&'a [Fast<F>]> for Strided<'a, F> {
+    fn from(xs: &'a [Fast<F>]) -> Self { Strided::new(xs, 1) }
+}
+
+impl<'a, F> StridedMut<'a, F> {
+    /// Create a mutable view of every `stride`th element of `xs`, starting with the first.
+    ///
+    /// ***Panics*** if `stride` is zero.
+    pub fn new(xs: &'a mut [Fast<F>], stride: usize) -> Self {
+        let len = strided_len(xs.len(), stride);
+        StridedMut { ptr: xs.as_mut_ptr(), len, stride, life: PhantomData }
+    }
+
+    /// Create a mutable view from a pointer, a length and a stride.
+    ///
+    /// # Safety
+    ///
+    /// `ptr.add(i * stride)` must be valid for reads and writes for each `i < len`, the
+    /// elements must be distinct (`stride` is not zero unless `len <= 1`), and they must not
+    /// be accessed through other pointers during the lifetime `'a`.
+    pub unsafe fn from_raw_parts(ptr: *mut Fast<F>, len: usize, stride: usize) -> Self {
+        StridedMut { ptr, len, stride, life: PhantomData }
+    }
+
+    /// Return the number of elements in the view
+    pub fn len(&self) -> usize { self.len }
+
+    /// Return `true` if the view has no elements
+    pub fn is_empty(&self) -> bool { self.len == 0 }
+
+    /// Return an immutable view of the same elements
+    pub fn as_strided(&self) -> Strided<'_, F> {
+        unsafe { Strided::from_raw_parts(self.ptr, self.len, self.stride) }
+    }
+
+    /// Return the element at `index` mutably, or `None` if it is out of bounds
+    pub fn get_mut(&mut self, index: usize) -> Option<&mut Fast<F>> {
+        if index < self.len {
+            unsafe { Some(&mut *self.ptr.add(index * self.stride)) }
+        } else {
+            None
+        }
+    }
+}
+
+impl<'a, F: FastFloat> StridedMut<'a, F> {
+    /// Compute `self[i] += a * xs[i]`.
+    ///
+    /// ***Panics*** if the views are not of equal length.
+    pub fn axpy(&mut self, a: Fast<F>, xs: Strided<'_, F>) {
+        assert_eq!(self.len, xs.len, "StridedMut::axpy: views must be of equal length");
+        if self.stride == 1 {
+            if let Some(xs) = xs.as_contiguous() {
+                let ys = unsafe { slice::from_raw_parts_mut(self.ptr, self.len) };
+                return crate::slice::axpy(a, xs, ys);
+            }
+        }
+        for (i, &x) in xs.iter().enumerate() {
+            unsafe {
+                let y = &mut *self.ptr.add(i * self.stride);
+                *y += a * x;
+            }
+        }
+    }
+}
+
+impl<'a, F> From<&'a mut [Fast<F>]> for StridedMut<'a, F> {
+    fn from(xs: &'a mut [Fast<F>]) -> Self { StridedMut::new(xs, 1) }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn views() {
+        let m: [Fast<f64>; 12] = std::array::from_fn(|i| Fast(i as f64));
+        let col = Strided::new(&m[2..], 3);
+        assert_eq!(col.len(), 4);
+        assert_eq!(col.get(3), Some(&Fast(11.)));
+        assert_eq!(col.get(4), None);
+        assert_eq!(col.sum(), Fast(26.));
+        assert_eq!(col.dot(Strided::new(&m[..4], 1)), Fast(2. * 0. + 5. + 8. * 2. + 11. * 3.));
+        assert_eq!(Strided::from(&m[..]).sum(), Fast(66.));
+        assert!(Strided::new(&m[..0], 2).is_empty());
+
+        let mut n = m;
+        let mut col = StridedMut::new(&mut n, 4);
+        col.axpy(Fast(2.), Strided::new(&m[..3], 1));
+        *col.get_mut(0).unwrap() = Fast(-1.);
+        assert_eq!(col.as_strided().sum(), Fast(17.));
+        assert_eq!([n[0], n[4], n[8]], [Fast(-1.), Fast(6.), Fast(12.)]);
+        StridedMut::from(&mut n[..3]).axpy(Fast(1.), Strided::new(&m[..3], 1));
+        assert_eq!(&n[..3], [Fast(-1.), Fast(2.), Fast(4.)]);
+    }
+}
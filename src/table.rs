@@ -0,0 +1,132 @@
+//! Lookup tables with interpolation.
+
+use crate::{Fast, FastFloat};
+
+/// A table of uniformly spaced samples of a function, with interpolating lookup.
+///
+/// The samples cover `x_min..=x_max`; lookups outside that range clamp to the first or last
+/// sample.
+///
+/// ```
+/// use fast_floats::table::Table1D;
+/// use fast_floats::Fast;
+///
+/// let f = |x| unsafe { Fast::new(x) };
+/// let samples = [f(0.), f(10.), f(20.), f(40.)];
+/// let table = Table1D::new(&samples, f(0.), f(3.));
+/// assert_eq!(table.lookup_linear(f(2.5)), f(30.));
+/// ```
+#[derive(Copy, Clone, Debug)]
+pub struct Table1D<'a, F> {
+    samples: &'a [Fast<F>],
+    x_min: Fast<F>,
+    x_max: Fast<F>,
+    inv_step: Fast<F>,
+}
+
+impl<'a, F: FastFloat> Table1D<'a, F> {
+    /// Create a table from `samples`, where the first sample is at `x_min` and the last
+    /// at `x_max`.
+    ///
+    /// ***Panics*** if there are fewer than two samples, if `x_max` is not greater than
+    /// `x_min`, or if the width of the range or the inverse of the sample spacing is not a
+    /// finite float.
+    pub fn new(samples: &'a [Fast<F>], x_min: Fast<F>, x_max: Fast<F>) -> Self {
+        assert!(samples.len() >= 2, "Table1D: needs at least two samples");
+        assert!(x_max > x_min, "Table1D: x_max must be greater than x_min");
+        // strict, since both overflow for extreme ranges
+        let width = F::sub_strict(x_max.0, x_min.0);
+        let inv_step = F::div_strict(F::from_f64((samples.len() - 1) as f64), width);
+        assert!(width.is_finite() && inv_step.is_finite(),
+                "Table1D: the range {:?}..={:?} is too wide or too narrow", x_min, x_max);
+        Table1D { samples, x_min, x_max, inv_step: Fast(inv_step) }
+    }
+
+    /// Return the samples
+    pub fn samples(&self) -> &'a [Fast<F>] { self.samples }
+
+    /// Return the sample index and fractional position for `x`
+    #[inline]
+    fn position(&self, x: Fast<F>) -> (usize, Fast<F>) {
+        let last = self.samples.len() - 1;
+        // clamp first, so that the offset and its product stay in range
+        let t = (x.clamp(self.x_min, self.x_max) - self.x_min) * self.inv_step;
+        if t <= Fast(F::ZERO) {
+            (0, Fast(F::ZERO))
+        } else {
+            let i = t.0.to_usize();
+            if i >= last {
+                (last - 1, Fast(F::ONE))
+            } else {
+                (i, t - F::from_f64(i as f64))
+            }
+        }
+    }
+
+    /// Look up `x` with linear interpolation between the neighboring samples.
+    #[inline]
+    pub fn lookup_linear(&self, x: Fast<F>) -> Fast<F> {
+        let (i, frac) = self.position(x);
+        let (a, b) = (self.samples[i], self.samples[i + 1]);
+        (b - a) * frac + a
+    }
+
+    /// Look up `x` with cubic (Catmull–Rom) interpolation of the four nearest samples.
+    ///
+    /// At the ends of the table the first and last samples are repeated.
+    #[inline]
+    pub fn lookup_cubic(&self, x: Fast<F>) -> Fast<F> {
+        let (i, t) = self.position(x);
+        let s = self.samples;
+        let p0 = s[i.saturating_sub(1)];
+        let (p1, p2) = (s[i], s[i + 1]);
+        let p3 = s[Ord::min(i + 2, s.len() - 1)];
+        let half = Fast(F::from_f64(0.5));
+        // Catmull–Rom in Horner form
+        let c1 = (p2 - p0) * half;
+        let c2 = p0 - p1 * F::from_f64(2.5) + p2 * F::from_f64(2.) - p3 * half;
+        let c3 = (p3 - p0) * half + (p1 - p2) * F::from_f64(1.5);
+        ((c3 * t + c2) * t + c1) * t + p1
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn lookup() {
+        let samples = [Fast(0.), Fast(1.), Fast(4.), Fast(9.), Fast(16.)];
+        let table = Table1D::new(&samples, Fast(0.), Fast(4.));
+        assert_eq!(table.lookup_linear(Fast(1.5)), Fast(2.5));
+        assert_eq!(table.lookup_linear(Fast(-1.)), Fast(0.));
+        assert_eq!(table.lookup_linear(Fast(4.)), Fast(16.));
+        assert_eq!(table.lookup_linear(Fast(10.)), Fast(16.));
+        assert_eq!(table.lookup_linear(Fast(f64::MAX)), Fast(16.));
+        assert_eq!(table.lookup_cubic(Fast(-f64::MAX)), Fast(0.));
+        // Catmull–Rom reproduces the quadratic away from the ends
+        assert_eq!(table.lookup_cubic(Fast(2.5)), Fast(6.25));
+        assert_eq!(table.lookup_cubic(Fast(3.)), Fast(9.));
+    }
+
+    #[test]
+    #[should_panic(expected = "x_max must be greater than x_min")]
+    fn empty_range() {
+        let samples = [Fast(0f32), Fast(1.)];
+        Table1D::new(&samples, Fast(1.), Fast(1.));
+    }
+
+    #[test]
+    #[should_panic(expected = "too wide or too narrow")]
+    fn wide_range() {
+        let samples = [Fast(0f64), Fast(1.)];
+        Table1D::new(&samples, Fast(-f64::MAX), Fast(f64::MAX));
+    }
+
+    #[test]
+    #[should_panic(expected = "too wide or too narrow")]
+    fn narrow_range() {
+        let samples = [Fast(0f64), Fast(1.), Fast(2.)];
+        Table1D::new(&samples, Fast(0.), Fast(1e-310));
+    }
+}
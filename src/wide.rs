@@ -0,0 +1,58 @@
+//! Conversions between arrays of fast values and the SIMD vectors of the `wide` crate.
+//!
+//! This module requires the `wide` feature. The `wide` vectors compute with regular float
+//! semantics with explicit SIMD on stable Rust; the conversions let the same lanes be used
+//! with both. The orphan rule does not allow `From` implementations between arrays and
+//! `wide` types in this crate, so the conversions are functions.
+//!
+//! ```
+//! use fast_floats::{wide, Fast};
+//!
+//! let xs = [1., 2., 3., 4.].map(|x| unsafe { Fast::new(x) });
+//! let v = wide::to_f64x4(xs) * ::wide::f64x4::splat(2.);
+//! let ys = unsafe { wide::from_f64x4(v) };
+//! assert_eq!(ys[3].get(), 8.);
+//! ```
+
+use ::wide::{f32x4, f32x8, f64x2, f64x4};
+
+use crate::Fast;
+
+macro_rules! conversions {
+    ($($to:ident, $from:ident, $v:ident, $t:ident, $n:expr;)*) => {
+        $(
+        #[doc = concat!("Convert an array of fast values to `", stringify!($v), "`")]
+        #[inline]
+        pub fn $to(xs: [Fast<$t>; $n]) -> $v { $v::new(xs.map(Fast::get)) }
+
+        #[doc = concat!("Convert the lanes of `", stringify!($v), "` to fast values.")]
+        ///
+        /// # Safety
+        ///
+        /// Each lane must be finite, see [`Fast::new`].
+        #[inline]
+        pub unsafe fn $from(v: $v) -> [Fast<$t>; $n] { v.to_array().map(|x| Fast(x)) }
+        )*
+    }
+}
+
+conversions! {
+    to_f32x4, from_f32x4, f32x4, f32, 4;
+    to_f32x8, from_f32x8, f32x8, f32, 8;
+    to_f64x2, from_f64x2, f64x2, f64, 2;
+    to_f64x4, from_f64x4, f64x4, f64, 4;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn roundtrip() {
+        let xs: [Fast<f32>; 8] = std::array::from_fn(|i| Fast(i as f32 - 3.5));
+        let v = to_f32x8(xs);
+        assert_eq!(v.reduce_add(), 0.);
+        assert_eq!(unsafe { from_f32x8(v.abs()) }[0], Fast(3.5));
+        assert_eq!(unsafe { from_f64x2(to_f64x2([Fast(1.), Fast(-0.)])) }, [Fast(1.), Fast(-0.)]);
+    }
+}
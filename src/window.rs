@@ -0,0 +1,76 @@
+//! Window functions for spectral analysis.
+//!
+//! Each function fills a buffer with the coefficients of a window across its whole length.
+//! The windows are symmetric: `out[i] == out[n - 1 - i]`, with the peak in the middle. For
+//! periodic windows, as used with the FFT, fill a buffer one longer than the transform and
+//! ignore the last coefficient.
+//!
+//! The coefficients use [`approx::cos`], so they are accurate to about single precision.
+//!
+//! ```
+//! use fast_floats::{window, Fast};
+//!
+//! let mut w = [unsafe { Fast::new(0f32) }; 5];
+//! window::hann(&mut w);
+//! assert_eq!(w.map(Fast::get), [0., 0.5, 1., 0.5, 0.]);
+//! ```
+
+use std::f64::consts::TAU;
+
+use crate::approx;
+use crate::{Fast, FastFloat};
+
+/// Fill `out` with the generalized cosine window `Σ (-1)^k a[k] cos(2πk i / (n - 1))`
+fn cosine_sum<F: FastFloat>(out: &mut [Fast<F>], a: &[f64]) {
+    let n = out.len();
+    if n <= 1 {
+        out.fill(Fast(F::ONE));
+        return;
+    }
+    let step = Fast(F::from_f64(TAU / (n - 1) as f64));
+    for (i, o) in out.iter_mut().enumerate() {
+        let x = step * F::from_f64(i as f64);
+        let mut sign = 1.;
+        *o = a.iter().enumerate().fold(Fast(F::ZERO), |acc, (k, &c)| {
+            let term = approx::cos(x * F::from_f64(k as f64)) * F::from_f64(sign * c);
+            sign = -sign;
+            acc + term
+        });
+    }
+}
+
+/// Fill `out` with the Hann window, `0.5 - 0.5 cos(2πi / (n - 1))`
+pub fn hann<F: FastFloat>(out: &mut [Fast<F>]) {
+    cosine_sum(out, &[0.5, 0.5])
+}
+
+/// Fill `out` with the Hamming window, `0.54 - 0.46 cos(2πi / (n - 1))`
+pub fn hamming<F: FastFloat>(out: &mut [Fast<F>]) {
+    cosine_sum(out, &[0.54, 0.46])
+}
+
+/// Fill `out` with the Blackman window,
+/// `0.42 - 0.5 cos(2πi / (n - 1)) + 0.08 cos(4πi / (n - 1))`
+pub fn blackman<F: FastFloat>(out: &mut [Fast<F>]) {
+    cosine_sum(out, &[0.42, 0.5, 0.08])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn windows() {
+        let mut w = [Fast(0f64); 9];
+        hamming(&mut w);
+        assert!((w[0].get() - 0.08).abs() < 1e-7 && (w[4].get() - 1.).abs() < 1e-7);
+        blackman(&mut w);
+        assert!(w[0].get().abs() < 1e-7 && (w[4].get() - 1.).abs() < 1e-7);
+        for i in 0..9 {
+            assert!((w[i] - w[8 - i]).abs() < Fast(1e-7));
+        }
+        let mut one = [Fast(0f32)];
+        hann(&mut one);
+        assert_eq!(one, [Fast(1.)]);
+    }
+}